@@ -0,0 +1,340 @@
+use crate::{error, lock, State, StateEntry, StateExt};
+
+/// The `Access` trait provides access to the value of the state by reference.
+///
+/// Unlike `Getter`, it does not clone the value and therefore does not require `T: Clone`.
+/// The closure runs while the lock is held, so it should be short and must not access
+/// the same state again.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Access, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (text, _) = Buffer::new_state(Some(String::from("state")));
+/// let len = text.with_read(|value| value.map_or(0, |s| s.len())).unwrap();
+/// assert_eq!(len, 5);
+/// ```
+pub trait Access<T> {
+    /// Calls `f` with a reference to the current value under the read lock.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure receiving the value, or `None` if the state is empty.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the result of `f`, or a `StateError` if the lock could not be acquired.
+    fn with_read<R, F: FnOnce(Option<&T>) -> R>(&self, f: F) -> error::Result<R>;
+
+    /// Calls `f` with a mutable reference to the stored value under the write lock.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure receiving the stored `Option<T>`.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the result of `f`, or a `StateError` if the lock could not be acquired.
+    fn with_write<R, F: FnOnce(&mut Option<T>) -> R>(&self, f: F) -> error::Result<R>;
+
+    /// Returns `f` applied to the current value, without cloning the value itself.
+    ///
+    /// This is a read-only projection: only the result of `f` is returned, so extracting a field
+    /// of a large value copies just that field.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The function computing the result from a reference to the value.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Some` with the result of `f`, or `None` if the state is empty or the lock is poisoned.
+    fn map_get<U, F: FnOnce(&T) -> U>(&self, f: F) -> Option<U>;
+
+    /// Calls `f` with a reference to the current value for its side effects only, e.g. logging.
+    ///
+    /// Unlike `with_read`, nothing is returned from the closure, and the value is not cloned.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure observing the value, or `None` if the state is empty.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` once `f` has run, or a `StateError` if the lock could not be acquired.
+    fn inspect<F: Fn(Option<&T>)>(&self, f: F) -> error::Result<()>;
+
+    /// Checks whether the state holds a value, without cloning it.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `true` if the state holds a value. If the lock is poisoned, the value cannot be
+    /// trusted, so `false` is returned.
+    fn is_some(&self) -> bool;
+
+    /// Checks whether the state is empty, without cloning its value.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `true` if the state is empty. If the lock is poisoned, the state is treated as
+    /// empty and `true` is returned, consistently with `is_some`.
+    fn is_none(&self) -> bool;
+
+    /// Compares the values of two states.
+    ///
+    /// Both states are locked for reading at the same time, so the comparison sees a consistent
+    /// pair of values. To avoid a deadlock when two threads compare the same states in opposite
+    /// order while writers are waiting, the locks are always acquired in the order of the
+    /// addresses of the shared state data, regardless of which state is `self`. Comparing a state
+    /// with itself or one of its clones takes the lock only once.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The state to compare with.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `true` if both states hold equal values or are both empty. If either lock is
+    /// poisoned, the values cannot be trusted, so `false` is returned.
+    fn content_eq(&self, other: &State<T>) -> bool
+        where T: PartialEq;
+
+    /// Locks the state for writing and returns an entry for conditional modifications.
+    ///
+    /// The lock is held until the entry is dropped, so all modifications made through it happen
+    /// atomically. The same restrictions as for `with_write` apply while the entry is alive.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the `StateEntry`, or a `StateError` if the lock could not be acquired.
+    fn entry(&self) -> error::Result<StateEntry<'_, T>>;
+}
+
+impl<T, S> Access<T> for S
+where S: StateExt<T>
+{
+    fn with_read<R, F: FnOnce(Option<&T>) -> R>(&self, f: F) -> error::Result<R> {
+        let state_guard = lock::read(self.state())?;
+        Ok(f(state_guard.as_ref()))
+    }
+
+    fn with_write<R, F: FnOnce(&mut Option<T>) -> R>(&self, f: F) -> error::Result<R> {
+        let mut state_guard = self.write_for_setter()?;
+        let result = f(&mut state_guard);
+        self.publish(state_guard);
+        Ok(result)
+    }
+
+    fn map_get<U, F: FnOnce(&T) -> U>(&self, f: F) -> Option<U> {
+        match lock::read(self.state()) {
+            Ok(state_guard) => state_guard.as_ref().map(f),
+            Err(_) => None,
+        }
+    }
+
+    fn inspect<F: Fn(Option<&T>)>(&self, f: F) -> error::Result<()> {
+        let state_guard = lock::read(self.state())?;
+        f(state_guard.as_ref());
+        Ok(())
+    }
+
+    fn is_some(&self) -> bool {
+        match lock::read(self.state()) {
+            Ok(state_guard) => state_guard.is_some(),
+            Err(_) => false,
+        }
+    }
+
+    fn is_none(&self) -> bool {
+        !self.is_some()
+    }
+
+    fn entry(&self) -> error::Result<StateEntry<'_, T>> {
+        Ok(StateEntry::new(self.meta(), self.write_for_setter()?))
+    }
+
+    fn content_eq(&self, other: &State<T>) -> bool
+        where T: PartialEq
+    {
+        if lock::address(self.state()) == lock::address(other) {
+            return lock::read(self.state()).is_ok();
+        }
+
+        let (first, second) = if lock::address(self.state()) < lock::address(other) {
+            (self.state(), other)
+        } else {
+            (other, self.state())
+        };
+        let (Ok(first_guard), Ok(second_guard)) = (lock::read(first), lock::read(second)) else {
+            return false;
+        };
+        *first_guard == *second_guard
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::{Arc, Mutex};
+    #[cfg(feature = "std")]
+    use std::thread;
+
+    #[cfg(all(feature = "std", not(feature = "parking-lot")))]
+    use crate::error::StateError;
+    use crate::tests::S;
+    use crate::{Access, Getter, StateManager};
+
+    #[test]
+    fn test_with_read_string_len() {
+        let (state, _) = S::new_state(Some(String::from("state-manager")));
+        let len = state.with_read(|value| value.map(String::len)).unwrap();
+        assert_eq!(len, Some(13));
+    }
+
+    #[test]
+    fn test_with_read_empty_state() {
+        let (state, _) = S::new_state(None::<String>);
+        assert_eq!(state.with_read(|value| value.map(String::len)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_with_write_returns_result() {
+        let (state, _) = S::new_state(Some(String::from("state")));
+        let len = state.with_write(|value| {
+            let s = value.get_or_insert_with(String::new);
+            s.push_str("-manager");
+            s.len()
+        }).unwrap();
+        assert_eq!(len, 13);
+        assert_eq!(state.get(), Some(String::from("state-manager")));
+    }
+
+    #[cfg(all(feature = "std", not(feature = "parking-lot")))]
+    #[test]
+    fn test_with_read_poisoned() {
+        let (state, _) = S::new_state(Some(String::from("state")));
+        let state_for_panic = state.clone();
+        let _ = thread::spawn(move || {
+            let _guard = state_for_panic.write().unwrap();
+            panic!("poison the lock");
+        }).join();
+
+        assert!(matches!(state.with_read(|_| ()), Err(StateError::Poisoned)));
+        assert!(matches!(state.with_write(|_| ()), Err(StateError::Poisoned)));
+        assert!(!state.is_some());
+        assert!(state.is_none());
+        assert_eq!(state.map_get(String::len), None);
+        assert!(matches!(state.inspect(|_| ()), Err(StateError::Poisoned)));
+    }
+
+    #[test]
+    fn test_is_some_populated() {
+        let (state, _) = S::new_state(Some(String::from("state")));
+        assert!(state.is_some());
+        assert!(!state.is_none());
+    }
+
+    #[test]
+    fn test_is_none_empty() {
+        let (state, _) = S::new_state(None::<String>);
+        assert!(state.is_none());
+        assert!(!state.is_some());
+    }
+
+    #[test]
+    fn test_non_clone_state() {
+        // Тип без Clone (и без Default) управляется состоянием и читается по ссылке
+        struct Handle {
+            id: u32,
+        }
+
+        let (state, set_state) = S::new_state(Some(Handle { id: 1 }));
+        assert_eq!(state.with_read(|handle| handle.map(|h| h.id)).unwrap(), Some(1));
+
+        set_state(Some(Handle { id: 2 })).unwrap();
+        assert_eq!(state.with_read(|handle| handle.map(|h| h.id)).unwrap(), Some(2));
+        assert!(state.is_some());
+    }
+
+    #[test]
+    fn test_map_get_field() {
+        struct User {
+            name: String,
+            history: Vec<String>,
+        }
+
+        let (state, _) = S::new_state(Some(User {
+            name: String::from("alice"),
+            history: vec![String::from("login"); 1000],
+        }));
+        // Клонируется только имя, а не вся структура
+        assert_eq!(state.map_get(|user| user.name.clone()), Some(String::from("alice")));
+        assert_eq!(state.map_get(|user| user.history.len()), Some(1000));
+    }
+
+    #[test]
+    fn test_map_get_empty_state() {
+        let (state, _) = S::new_state(None::<String>);
+        assert_eq!(state.map_get(String::len), None);
+    }
+
+    #[test]
+    fn test_inspect_records_value() {
+        let (state, set_state) = S::new_state(Some(String::from("first")));
+        let observed = Arc::new(Mutex::new(Vec::new()));
+
+        let observed_clone = observed.clone();
+        state.inspect(|value| observed_clone.lock().unwrap().push(value.cloned())).unwrap();
+        set_state(None).unwrap();
+        state.inspect(|value| observed_clone.lock().unwrap().push(value.cloned())).unwrap();
+
+        assert_eq!(*observed.lock().unwrap(), vec![Some(String::from("first")), None]);
+        // Состояние не изменяется
+        assert_eq!(state.get(), None);
+    }
+
+    #[test]
+    fn test_content_eq() {
+        let (first, set_first) = S::new_state(Some(String::from("a")));
+        let (second, set_second) = S::new_state(Some(String::from("a")));
+        assert!(first.content_eq(&second));
+        assert!(second.content_eq(&first));
+        assert!(first.content_eq(&first.clone()));
+
+        set_second(Some(String::from("b"))).unwrap();
+        assert!(!first.content_eq(&second));
+
+        // Два пустых состояния равны
+        set_first(None).unwrap();
+        set_second(None).unwrap();
+        assert!(first.content_eq(&second));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_content_eq_opposite_order_does_not_deadlock() {
+        let (first, set_first) = S::new_state(Some(0));
+        let (second, _) = S::new_state(Some(0));
+        let mut handles = vec![];
+
+        for i in 0..4 {
+            let (first, second, set_first) = (first.clone(), second.clone(), set_first.clone());
+            handles.push(thread::spawn(move || {
+                for j in 0..200 {
+                    if i % 2 == 0 {
+                        first.content_eq(&second);
+                    } else {
+                        second.content_eq(&first);
+                    }
+                    set_first(Some(j)).unwrap();
+                }
+            }));
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+}
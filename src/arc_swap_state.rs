@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use arc_swap::ArcSwapOption;
+
+use crate::{error, ArcGetter, Getter, StateSetter};
+
+/// Defines the `ArcSwapState` type as an atomically reference-counted `ArcSwapOption` holding
+/// an optional value of type `T`.
+///
+/// Reads of an `ArcSwapState` never lock: they load a pointer to the current value, so readers
+/// never wait for each other or for writers. Writes atomically swap in a new value and never
+/// modify the stored one, which is therefore kept in an `Arc`. Read the shared value through
+/// `ArcGetter::get_arc`, or clone it out through `Getter`.
+///
+/// Prefer it over `State` for read-heavy workloads whose values are replaced as a whole.
+/// Like `MutexState`, it only supports `Getter`, `ArcGetter` and its setter: observers, history
+/// and in-place updates are not available.
+///
+/// # Example
+///
+/// ```
+/// use state_manager::{ArcGetter, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (config, set_config) = Buffer::new_arc_swap_state(Some(String::from("v1")));
+/// set_config(Some(String::from("v2"))).unwrap();
+/// assert_eq!(config.get_arc().as_deref(), Some(&String::from("v2")));
+/// ```
+pub type ArcSwapState<T> = Arc<ArcSwapOption<T>>;
+
+impl<T> Getter<T> for ArcSwapState<T>
+where T: Clone
+{
+    fn get(&self) -> Option<T> {
+        self.load().as_deref().cloned()
+    }
+
+    /// Same as `get`: reads never block.
+    fn try_get(&self) -> Option<T> {
+        self.get()
+    }
+
+    /// Same as `get`: reads never fail.
+    fn try_get_result(&self) -> error::Result<Option<T>> {
+        Ok(self.get())
+    }
+
+    fn get_or(&self, default: T) -> T {
+        self.get().unwrap_or(default)
+    }
+
+    fn get_or_default(&self) -> T
+        where T: Default
+    {
+        self.get().unwrap_or_default()
+    }
+
+    fn get_or_else<F: FnOnce() -> T>(&self, f: F) -> T {
+        self.get().unwrap_or_else(f)
+    }
+
+    fn filter_get<F: Fn(&T) -> bool>(&self, pred: F) -> Option<T> {
+        self.load().as_deref().filter(|value| pred(value)).cloned()
+    }
+}
+
+impl<T> ArcGetter<T> for ArcSwapState<T> {
+    fn get_arc(&self) -> Option<Arc<T>> {
+        self.load_full()
+    }
+}
+
+/// Creates a new `ArcSwapState` and its setter. Used by `StateManager::new_arc_swap_state`.
+pub(crate) fn new_arc_swap_state<S>(data: Option<S>) -> (ArcSwapState<S>, StateSetter<S>)
+    where
+    S: Send + Sync + 'static,
+{
+    let state = Arc::new(ArcSwapOption::new(data.map(Arc::new)));
+    let state_for_setter = state.clone();
+    let setter = move |data: Option<S>| -> error::Result<()> {
+        state_for_setter.store(data.map(Arc::new));
+        Ok(())
+    };
+    (state, Arc::new(setter))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use crate::tests::S;
+    use crate::{ArcGetter, Getter, StateManager};
+
+    #[test]
+    fn test_arc_swap_get_and_set() {
+        let (state, set_state) = S::new_arc_swap_state(Some(1));
+        assert_eq!(state.get(), Some(1));
+
+        set_state(None).unwrap();
+        assert_eq!(state.get(), None);
+        assert_eq!(state.get_or(5), 5);
+
+        set_state(Some(2)).unwrap();
+        assert_eq!(state.filter_get(|value| *value > 1), Some(2));
+    }
+
+    #[test]
+    fn test_arc_swap_get_arc_shares_value() {
+        let (state, _) = S::new_arc_swap_state(Some(String::from("shared")));
+        let first = state.get_arc().unwrap();
+        let second = state.get_arc().unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_arc_swap_concurrent_reads_during_writes() {
+        // Значение всегда состоит из одинаковых чисел => разорванное чтение заметно
+        let (state, set_state) = S::new_arc_swap_state(Some(vec![0usize; 64]));
+        let writer = thread::spawn(move || {
+            for i in 1..=1000 {
+                set_state(Some(vec![i; 64])).unwrap();
+            }
+        });
+        let mut readers = vec![];
+
+        for _ in 0..4 {
+            let state = state.clone();
+            readers.push(thread::spawn(move || {
+                let mut last = 0;
+                for _ in 0..10_000 {
+                    let value = state.get_arc().unwrap();
+                    assert!(value.iter().all(|item| *item == value[0]));
+                    // Читатель никогда не видит более старое значение
+                    assert!(value[0] >= last);
+                    last = value[0];
+                }
+            }));
+        }
+
+        writer.join().unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(state.get().unwrap()[0], 1000);
+    }
+}
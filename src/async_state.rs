@@ -0,0 +1,129 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::error;
+
+/// Defines the `AsyncState` type as an atomically reference-counted asynchronous read/write lock
+/// containing an optional value of type `T`.
+///
+/// Unlike `State`, acquiring the lock of an `AsyncState` never blocks the executor thread:
+/// readers and writers wait by awaiting instead.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use state_manager::AsyncState;
+///
+/// let state: AsyncState<i32> = Arc::new(tokio::sync::RwLock::new(Some(42)));
+/// ```
+pub type AsyncState<T> = Arc<tokio::sync::RwLock<Option<T>>>;
+
+/// A type alias for a reference-counted dynamic closure that modifies an `AsyncState`.
+///
+/// Calling the closure returns a future which completes once the new value is written.
+/// Both the closure and the returned future are `Send`, so they can be used from any task.
+/// Like `StateSetter`, it can be cloned and moved into several tasks.
+///
+/// # Examples
+///
+/// ```rust
+/// use state_manager::{AsyncGetter, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let (state, set_state) = Buffer::new_async_state(Some(1));
+/// set_state(Some(2)).await.unwrap();
+/// assert_eq!(state.get().await, Some(2));
+/// # });
+/// ```
+pub type AsyncStateSetter<T> = Arc<
+    dyn Fn(Option<T>) -> Pin<Box<dyn Future<Output = error::Result<()>> + Send>> + Send + Sync
+>;
+
+/// The `AsyncGetter` trait provides an asynchronous `get` method for retrieving the value from an `AsyncState`.
+pub trait AsyncGetter<T> {
+    /// Returns the current value of the state, if it exists, waiting for writers without blocking the thread.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a future resolving to `Option<T>`, where `T` is the type of the value stored in the state.
+    fn get(&self) -> impl Future<Output = Option<T>> + Send;
+}
+
+impl<T> AsyncGetter<T> for AsyncState<T>
+where T: Clone + Send + Sync
+{
+    async fn get(&self) -> Option<T> {
+        let state_guard = self.read().await;
+        state_guard.clone()
+    }
+}
+
+/// Creates a new `AsyncState` and its setter. Used by `StateManager::new_async_state`.
+pub(crate) fn new_async_state<S>(data: Option<S>) -> (AsyncState<S>, AsyncStateSetter<S>)
+    where
+    S: Send + Sync + 'static,
+{
+    let state = Arc::new(tokio::sync::RwLock::new(data));
+    let state_for_setter = state.clone();
+    let setter = move |data: Option<S>| -> Pin<Box<dyn Future<Output = error::Result<()>> + Send>> {
+        let state = state_for_setter.clone();
+        Box::pin(async move {
+            let mut state_guard = state.write().await;
+            *state_guard = data;
+            Ok(())
+        })
+    };
+    (state, Arc::new(setter))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::tests::S;
+    use crate::{AsyncGetter, StateManager};
+
+    #[tokio::test]
+    async fn test_async_init_and_set() {
+        let (state, set_state) = S::new_async_state(Some(42));
+        assert_eq!(state.get().await, Some(42));
+        set_state(None).await.unwrap();
+        assert_eq!(state.get().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_async_setter_clone() {
+        let (state, set_state) = S::new_async_state(Some(0));
+        let set_state_clone = set_state.clone();
+        // Клон сеттера переносится в другую задачу
+        tokio::spawn(async move {
+            set_state_clone(Some(1)).await.unwrap();
+        }).await.unwrap();
+        assert_eq!(state.get().await, Some(1));
+        set_state(Some(2)).await.unwrap();
+        assert_eq!(state.get().await, Some(2));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_async_concurrent_reads_and_write() {
+        let (state, set_state) = S::new_async_state(Some(0));
+        let mut handles = vec![];
+
+        for _ in 0..10 {
+            let state_clone = state.clone();
+            handles.push(tokio::spawn(async move {
+                let value = state_clone.get().await;
+                assert!(value == Some(0) || value == Some(42));
+            }));
+        }
+        set_state(Some(42)).await.unwrap();
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert_eq!(state.get().await, Some(42));
+    }
+}
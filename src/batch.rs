@@ -0,0 +1,362 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use crate::error::{self, StateError};
+use crate::{lock, Getter, ManagedState, State, StateExt};
+
+/// Writes the same value to every state in `states`, in order.
+///
+/// The writes are independent: each state is locked and notified on its own, so observers of
+/// one state may run before the next state is written. Writing stops at the first failure,
+/// leaving the states before it updated and the states after it untouched.
+///
+/// # Arguments
+///
+/// * `states` - The states to write to.
+/// * `value` - The new value, cloned into each state.
+///
+/// # Return Value
+///
+/// Returns `Ok(())` once every state is written, or `StateError::Default` naming the index of the
+/// failing state in `states` and the error it returned.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{set_all, Getter, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (first, _) = Buffer::new_state(Some(1));
+/// let (second, _) = Buffer::new_state(None);
+/// set_all(&[first.clone(), second.clone()], Some(0)).unwrap();
+/// assert_eq!(first.get(), Some(0));
+/// assert_eq!(second.get(), Some(0));
+/// ```
+pub fn set_all<T: Clone>(states: &[ManagedState<T>], value: Option<T>) -> error::Result<()> {
+    for (index, state) in states.iter().enumerate() {
+        state.set(value.clone())
+            .map_err(|e| StateError::Default(alloc::format!("state {}: {}", index, e)))?;
+    }
+    Ok(())
+}
+
+/// Reads the value of every state in `states`, in order.
+///
+/// Each state is read independently with a blocking read lock, exactly like `Getter::get`,
+/// so this is not a globally consistent snapshot: a state may be written after it has been
+/// read but before the next one is. A poisoned state reads as `None`.
+///
+/// # Arguments
+///
+/// * `states` - The states to read, either `State`s or `ManagedState`s.
+///
+/// # Return Value
+///
+/// Returns the values of the states, in the order of `states`.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{get_all, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (first, _) = Buffer::new_state(Some(1));
+/// let (second, _) = Buffer::new_state(None);
+/// assert_eq!(get_all(&[first, second]), vec![Some(1), None]);
+/// ```
+pub fn get_all<T: Clone, S: Borrow<State<T>>>(states: &[S]) -> Vec<Option<T>> {
+    states.iter().map(|state| state.borrow().get()).collect()
+}
+
+/// Reads the value of every state in `states` as one consistent snapshot.
+///
+/// Unlike `get_all`, the read locks of all states are held at the same time while the values
+/// are cloned, so no write can land between two reads. To avoid deadlocks with other code
+/// locking several states, the locks are always acquired in the order of the addresses of the
+/// shared values, the same order used by `Swapper::swap_with` and `Access::content_eq`.
+/// A state passed more than once is locked only once. A poisoned state reads as `None`.
+///
+/// # Arguments
+///
+/// * `states` - The states to read.
+///
+/// # Return Value
+///
+/// Returns the values of the states, in the order of `states`.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{snapshot_consistent, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (first, _) = Buffer::new_state(Some(1));
+/// let (second, _) = Buffer::new_state(None);
+/// assert_eq!(snapshot_consistent(&[&first, &second, &first]), vec![Some(1), None, Some(1)]);
+/// ```
+pub fn snapshot_consistent<T: Clone>(states: &[&State<T>]) -> Vec<Option<T>> {
+    let mut order: Vec<&State<T>> = states.to_vec();
+    order.sort_by_key(|state| lock::address(state));
+    order.dedup_by_key(|state| lock::address(state));
+
+    let guards: Vec<_> = order.iter()
+        .map(|state| (lock::address(state), lock::read(state).ok()))
+        .collect();
+
+    states.iter()
+        .map(|state| {
+            let position = guards.binary_search_by_key(&lock::address(state), |(address, _)| *address)
+                .expect("every state is locked");
+            guards[position].1.as_ref().and_then(|state_guard| (**state_guard).clone())
+        })
+        .collect()
+}
+
+/// Locks every state in `states` for writing and passes their values to `f` as one transaction.
+///
+/// All write locks are held while `f` runs, so readers see either every change made by `f` or
+/// none of them. The locks are acquired in the order of the addresses of the shared values,
+/// like in `snapshot_consistent` and `Swapper::swap_with`, so concurrent calls with the same
+/// states in any order cannot deadlock. Every state is published once all locks are released.
+///
+/// # Arguments
+///
+/// * `states` - The states to lock. Each state may appear only once.
+/// * `f` - The closure receiving the values of the states, in the order of `states`.
+///
+/// # Return Value
+///
+/// Returns the result of `f`, `StateError::Default` if a state is passed more than once,
+/// `StateError::Frozen` if a state is frozen, or a `StateError` if a lock could not be acquired,
+/// in which case `f` is not called.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{with_locked, Getter, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (from, _) = Buffer::new_state(Some(10));
+/// let (to, _) = Buffer::new_state(Some(0));
+/// with_locked(&[&from, &to], |values| {
+///     *values[0] = values[0].map(|balance| balance - 3);
+///     *values[1] = values[1].map(|balance| balance + 3);
+/// }).unwrap();
+/// assert_eq!((from.get(), to.get()), (Some(7), Some(3)));
+/// ```
+pub fn with_locked<T, R, F>(states: &[&ManagedState<T>], f: F) -> error::Result<R>
+    where
+    F: FnOnce(&mut [&mut Option<T>]) -> R,
+{
+    let mut order: Vec<usize> = (0..states.len()).collect();
+    order.sort_by_key(|&index| lock::address(states[index]));
+    if let Some(pair) = order.windows(2).find(|pair| lock::address(states[pair[0]]) == lock::address(states[pair[1]])) {
+        return Err(StateError::Default(alloc::format!(
+            "State at index {} is passed more than once",
+            pair[0].max(pair[1])
+        )));
+    }
+
+    let mut guards = Vec::with_capacity(order.len());
+    for index in order {
+        guards.push((index, states[index].write_for_setter()?));
+    }
+    guards.sort_by_key(|(index, _)| *index);
+
+    let mut values: Vec<&mut Option<T>> = guards.iter_mut().map(|(_, state_guard)| &mut **state_guard).collect();
+    let result = f(&mut values);
+    drop(values);
+
+    // Уведомляем только после освобождения всех блокировок
+    for (index, state_guard) in &guards {
+        states[*index].prepare_publish(state_guard);
+    }
+    drop(guards);
+    for state in states {
+        state.complete_publish();
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::tests::S;
+    use crate::error::StateError;
+    use crate::{get_all, set_all, snapshot_consistent, with_locked, Getter, State, StateManager};
+
+    #[test]
+    fn test_set_all() {
+        let (first, _) = S::new_state(Some(1));
+        let (second, _) = S::new_state(None);
+        let (third, _) = S::new_state(Some(3));
+        set_all(&[first.clone(), second.clone(), third.clone()], Some(7)).unwrap();
+        assert_eq!(first.get(), Some(7));
+        assert_eq!(second.get(), Some(7));
+        assert_eq!(third.get(), Some(7));
+    }
+
+    #[test]
+    fn test_get_all_mixed() {
+        let (first, _) = S::new_state(Some(1));
+        let (second, _) = S::new_state(None);
+        let (third, _) = S::new_state(Some(3));
+        assert_eq!(get_all(&[first, second, third]), vec![Some(1), None, Some(3)]);
+        assert!(get_all::<i32, State<i32>>(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_consistent_order_and_duplicates() {
+        let (first, _) = S::new_state(Some(1));
+        let (second, _) = S::new_state(None);
+        let (third, _) = S::new_state(Some(3));
+        assert_eq!(
+            snapshot_consistent(&[&third, &first, &second, &first]),
+            vec![Some(3), Some(1), None, Some(1)]
+        );
+        assert!(snapshot_consistent::<i32>(&[]).is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_snapshot_consistent_with_concurrent_swaps() {
+        use std::thread;
+        use crate::Swapper;
+
+        let (left, _) = S::new_state(Some(1));
+        let (right, _) = S::new_state(Some(2));
+
+        // обмен держит обе блокировки, так что снимок всегда видит {1, 2}
+        let swappers: Vec<_> = (0..2).map(|i| {
+            let (left, right) = (left.clone(), right.clone());
+            thread::spawn(move || {
+                for _ in 0..2_000 {
+                    if i == 0 {
+                        left.swap_with(&right).unwrap();
+                    } else {
+                        right.swap_with(&left).unwrap();
+                    }
+                }
+            })
+        }).collect();
+
+        for _ in 0..2_000 {
+            let snapshot = snapshot_consistent(&[&left, &right]);
+            let (a, b) = (snapshot[0].unwrap(), snapshot[1].unwrap());
+            assert_eq!((a.min(b), a.max(b)), (1, 2));
+        }
+
+        for swapper in swappers {
+            swapper.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_with_locked_transfer() {
+        let (from, _) = S::new_state(Some(10));
+        let (to, _) = S::new_state(Some(0));
+        let moved = with_locked(&[&from, &to], |values| {
+            let amount = 4;
+            *values[0] = values[0].map(|balance| balance - amount);
+            *values[1] = values[1].map(|balance| balance + amount);
+            amount
+        }).unwrap();
+        assert_eq!(moved, 4);
+        assert_eq!(from.get(), Some(6));
+        assert_eq!(to.get(), Some(4));
+    }
+
+    #[test]
+    fn test_with_locked_rejects_duplicates() {
+        let (state, _) = S::new_state(Some(1));
+        match with_locked(&[&state, &state], |_| ()) {
+            Err(StateError::Default(message)) => assert_eq!(message, "State at index 1 is passed more than once"),
+            other => panic!("expected a duplicate error, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_with_locked_concurrent_transfers() {
+        use std::thread;
+
+        let (left, _) = S::new_state(Some(1_000));
+        let (right, _) = S::new_state(Some(1_000));
+
+        // переводы в обе стороны с разным порядком аргументов не должны зависать
+        let handles: Vec<_> = (0..4).map(|i| {
+            let (left, right) = (left.clone(), right.clone());
+            thread::spawn(move || {
+                for _ in 0..500 {
+                    let states = if i % 2 == 0 { [&left, &right] } else { [&right, &left] };
+                    with_locked(&states, |values| {
+                        *values[0] = values[0].map(|balance| balance - 1);
+                        *values[1] = values[1].map(|balance| balance + 1);
+                    }).unwrap();
+                }
+            })
+        }).collect();
+
+        for _ in 0..500 {
+            let snapshot = snapshot_consistent(&[&left, &right]);
+            assert_eq!(snapshot[0].unwrap() + snapshot[1].unwrap(), 2_000);
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(left.get(), Some(1_000));
+        assert_eq!(right.get(), Some(1_000));
+    }
+
+    #[test]
+    fn test_set_all_empty_slice() {
+        assert!(set_all::<i32>(&[], Some(1)).is_ok());
+    }
+
+    #[test]
+    fn test_set_all_reports_error_of_frozen_state() {
+        use crate::Freeze;
+
+        let (first, _) = S::new_state(Some(1));
+        let (second, _) = S::new_state(Some(2));
+        second.freeze();
+        assert_eq!(
+            set_all(&[first.clone(), second.clone()], Some(0)),
+            Err(StateError::Default(String::from("state 1: The state is frozen and cannot be written")))
+        );
+        assert_eq!(first.get(), Some(0));
+        assert_eq!(second.get(), Some(2));
+    }
+
+    #[cfg(all(feature = "std", not(feature = "parking-lot")))]
+    #[test]
+    fn test_set_all_reports_failing_index() {
+        use std::thread;
+
+        let (first, _) = S::new_state(Some(1));
+        let (second, _) = S::new_state(Some(2));
+        let (third, _) = S::new_state(Some(3));
+        let second_for_panic = second.clone();
+        let _ = thread::spawn(move || {
+            let _guard = second_for_panic.write().unwrap();
+            panic!("poison the lock");
+        }).join();
+
+        assert_eq!(
+            set_all(&[first.clone(), second, third.clone()], Some(0)),
+            Err(StateError::Default(String::from(
+                "state 1: State lock is poisoned: another thread panicked while holding it"
+            )))
+        );
+        // первое состояние уже записано, третье не тронуто
+        assert_eq!(first.get(), Some(0));
+        assert_eq!(third.get(), Some(3));
+    }
+}
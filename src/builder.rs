@@ -0,0 +1,150 @@
+use crate::{ManagedState, StateSetter};
+
+/// The `StateBuilder` configures the features of a new state before creating it.
+///
+/// Like `StateManager::new_state`, a built state notifies its observers about every write. The
+/// history is opt-in, and notifications can be turned off with `without_notifications`: such a
+/// state never calls its observers, so subscriptions, `on_change` callbacks and derived states of
+/// it are not updated. In exchange its writes skip the notification step.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Derive, Getter, History, StateBuilder};
+///
+/// let (state, set_state) = StateBuilder::new()
+///     .initial(1)
+///     .with_history(8)
+///     .build();
+/// let doubled = state.map(|value| value * 2);
+///
+/// set_state(Some(2)).unwrap();
+/// assert_eq!(doubled.get(), Some(4));
+///
+/// state.undo().unwrap();
+/// assert_eq!(state.get(), Some(1));
+/// ```
+pub struct StateBuilder<T> {
+    initial: Option<T>,
+    history: Option<usize>,
+    notifications: bool,
+}
+
+impl<T> StateBuilder<T> {
+    /// Creates a builder for an empty state without history, which notifies its observers.
+    pub fn new() -> Self {
+        StateBuilder {
+            initial: None,
+            history: None,
+            notifications: true,
+        }
+    }
+
+    /// Sets the initial value of the state. Without it, the state starts as `None`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The initial value.
+    pub fn initial(mut self, value: T) -> Self {
+        self.initial = Some(value);
+        self
+    }
+
+    /// Makes the state keep a history of the values replaced by its setter, as
+    /// `StateManager::new_history_state` does.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of previous values to keep.
+    pub fn with_history(mut self, capacity: usize) -> Self {
+        self.history = Some(capacity);
+        self
+    }
+
+    /// Makes the state notify its observers about every write.
+    ///
+    /// This is the default, so it only undoes an earlier `without_notifications`.
+    pub fn with_notifications(mut self) -> Self {
+        self.notifications = true;
+        self
+    }
+
+    /// Makes the state skip notifying its observers, which are then never called.
+    pub fn without_notifications(mut self) -> Self {
+        self.notifications = false;
+        self
+    }
+
+    /// Creates the configured state.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a tuple of `ManagedState<T>` and a function for modifying the state.
+    pub fn build(self) -> (ManagedState<T>, StateSetter<T>)
+        where T: Send + Sync + 'static
+    {
+        let state = ManagedState::configured(self.initial, self.history, self.notifications, None);
+        let setter = state.setter();
+        (state, setter)
+    }
+}
+
+impl<T> Default for StateBuilder<T> {
+    fn default() -> Self {
+        StateBuilder::new()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::{Derive, Getter, History, StateBuilder};
+
+    #[test]
+    fn test_build_without_initial() {
+        let (state, set_state) = StateBuilder::<i32>::new().build();
+        assert_eq!(state.get(), None);
+
+        set_state(Some(1)).unwrap();
+        assert_eq!(state.get(), Some(1));
+        // История не ведётся
+        assert!(!state.undo().unwrap());
+    }
+
+    #[test]
+    fn test_build_notifies_by_default() {
+        let (state, set_state) = StateBuilder::new().initial(1).build();
+        let doubled = state.map(|value| value * 2);
+
+        set_state(Some(2)).unwrap();
+        assert_eq!(doubled.get(), Some(4));
+    }
+
+    #[test]
+    fn test_build_with_history_and_notifications() {
+        let (state, set_state) = StateBuilder::new()
+            .initial(String::from("a"))
+            .with_history(4)
+            .without_notifications()
+            .with_notifications()
+            .build();
+        let length = state.map(String::len);
+
+        set_state(Some(String::from("bb"))).unwrap();
+        assert_eq!(length.get(), Some(2));
+
+        assert!(state.undo().unwrap());
+        assert_eq!(state.get(), Some(String::from("a")));
+        assert_eq!(length.get(), Some(1));
+    }
+
+    #[test]
+    fn test_build_without_notifications() {
+        let (state, set_state) = StateBuilder::new().initial(1).without_notifications().build();
+        let doubled = state.map(|value| value * 2);
+
+        set_state(Some(2)).unwrap();
+        assert_eq!(state.get(), Some(2));
+        // Производное состояние не обновляется без уведомлений
+        assert_eq!(doubled.get(), Some(2));
+    }
+}
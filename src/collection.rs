@@ -0,0 +1,214 @@
+use alloc::vec::Vec;
+use core::mem;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::{error, lock, State, StateExt};
+
+/// The `VecState` trait provides in-place operations on states holding a `Vec`.
+///
+/// Each operation runs under a single write lock, so no write is lost between reading the
+/// vector and storing it back, and the vector is never cloned.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{StateBuffer, StateManager, VecState};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (queue, _) = Buffer::new_state(Some(vec![1]));
+/// queue.push(2).unwrap();
+/// assert_eq!(queue.drain_vec().unwrap(), vec![1, 2]);
+/// assert!(queue.drain_vec().unwrap().is_empty());
+/// ```
+pub trait VecState<T> {
+    /// Takes the contents of the vector, leaving an empty one in its place.
+    ///
+    /// This suits a consumer collecting everything produced since its last call. Draining an
+    /// empty state returns an empty vector and leaves the state empty.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the previous contents, or a `StateError` if the lock could not be acquired.
+    fn drain_vec(&self) -> error::Result<Vec<T>>;
+
+    /// Appends `item` to the vector, creating a single-element vector if the state is empty.
+    ///
+    /// This replaces the read-clone-push-set cycle, which would lose items pushed concurrently.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The item to append.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` once the item is appended, or a `StateError` if the lock could not be
+    /// acquired.
+    fn push(&self, item: T) -> error::Result<()>;
+}
+
+impl<T, S> VecState<T> for S
+where S: StateExt<Vec<T>>
+{
+    fn drain_vec(&self) -> error::Result<Vec<T>> {
+        let mut state_guard = self.write_for_setter()?;
+        let drained = match state_guard.as_mut() {
+            Some(items) => mem::take(items),
+            None => return Ok(Vec::new()),
+        };
+        self.publish(state_guard);
+        Ok(drained)
+    }
+
+    fn push(&self, item: T) -> error::Result<()> {
+        let mut state_guard = self.write_for_setter()?;
+        state_guard.get_or_insert_with(Vec::new).push(item);
+        self.publish(state_guard);
+        Ok(())
+    }
+}
+
+/// The `Len` trait provides the size of a collection held by a state without cloning it.
+///
+/// It is implemented for states holding a `Vec` and, with the `std` feature, a `HashMap`.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Len, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (queue, _) = Buffer::new_state(Some(vec![1, 2, 3]));
+/// assert_eq!(queue.len(), 3);
+/// assert!(!queue.is_empty());
+/// ```
+pub trait Len {
+    /// Returns the number of elements in the collection.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the length under the read lock, or `0` if the state is empty or the lock is
+    /// poisoned.
+    fn len(&self) -> usize;
+
+    /// Returns whether the collection has no elements.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `true` if the collection is empty, the state is empty, or the lock is poisoned.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Returns `len` applied to the value of `state` under the read lock, or `0` if there is none.
+fn length<C, F: FnOnce(&C) -> usize>(state: &State<C>, len: F) -> usize {
+    match lock::read(state) {
+        Ok(state_guard) => state_guard.as_ref().map_or(0, len),
+        Err(_) => 0,
+    }
+}
+
+impl<T> Len for State<Vec<T>> {
+    fn len(&self) -> usize {
+        length(self, Vec::len)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> Len for State<HashMap<K, V, S>> {
+    fn len(&self) -> usize {
+        length(self, HashMap::len)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::tests::S;
+    use crate::{Getter, Len, StateManager, VecState};
+
+    #[test]
+    fn test_drain_vec() {
+        let (queue, _) = S::new_state(Some(Vec::new()));
+        for item in [1, 2, 3] {
+            queue.push(item).unwrap();
+        }
+        assert_eq!(queue.drain_vec().unwrap(), vec![1, 2, 3]);
+        assert_eq!(queue.get(), Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_drain_vec_empty_state() {
+        let (queue, _) = S::new_state(None::<Vec<i32>>);
+        assert!(queue.drain_vec().unwrap().is_empty());
+        assert_eq!(queue.get(), None);
+    }
+
+    #[test]
+    fn test_push_into_empty_state() {
+        let (queue, _) = S::new_state(None);
+        queue.push("first").unwrap();
+        assert_eq!(queue.get(), Some(vec!["first"]));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_push_from_many_threads() {
+        use std::thread;
+
+        let (queue, _) = S::new_state(None);
+        let handles: Vec<_> = (0..8).map(|thread_index| {
+            let queue = queue.clone();
+            thread::spawn(move || {
+                for item in 0..100 {
+                    queue.push(thread_index * 100 + item).unwrap();
+                }
+            })
+        }).collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // ни один элемент не потерян
+        let mut items = queue.drain_vec().unwrap();
+        items.sort_unstable();
+        assert_eq!(items, (0..800).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_len_vec() {
+        let (queue, _) = S::new_state(Some(vec![1, 2, 3]));
+        assert_eq!(queue.len(), 3);
+        assert!(!queue.is_empty());
+
+        queue.drain_vec().unwrap();
+        assert_eq!(queue.len(), 0);
+        assert!(queue.is_empty());
+
+        let (absent, _) = S::new_state(None::<Vec<i32>>);
+        assert_eq!(absent.len(), 0);
+        assert!(absent.is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_len_hash_map() {
+        use std::collections::HashMap;
+
+        let (map, _) = S::new_state(Some(HashMap::from([("a", 1), ("b", 2)])));
+        assert_eq!(map.len(), 2);
+        assert!(!map.is_empty());
+
+        let (empty, _) = S::new_state(Some(HashMap::<&str, i32>::new()));
+        assert_eq!(empty.len(), 0);
+        assert!(empty.is_empty());
+
+        let (absent, _) = S::new_state(None::<HashMap<&str, i32>>);
+        assert_eq!(absent.len(), 0);
+        assert!(absent.is_empty());
+    }
+}
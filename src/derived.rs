@@ -0,0 +1,400 @@
+use alloc::sync::Arc;
+
+use crate::{error, lock, Getter, ManagedState, State, StateExt, StateSetter};
+
+/// The `Derive` trait provides states whose values are computed from another state.
+///
+/// A derived state is recomputed after every write to its source, so it always stays in sync.
+/// It is recomputed from the current value of the source rather than from the notified one,
+/// so concurrent writes to the source cannot leave it holding an outdated value.
+/// The source only keeps a weak reference to the derived state: once all clones of the derived
+/// state are dropped, it is no longer updated. Values written to the derived state directly
+/// are overwritten by the next change of the source.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Derive, Getter, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (count, set_count) = Buffer::new_state(Some(1));
+/// let doubled = count.map(|count| count * 2);
+/// set_count(Some(21)).unwrap();
+/// assert_eq!(doubled.get(), Some(42));
+/// ```
+pub trait Derive<T> {
+    /// Creates a state holding `f` applied to the value of this state.
+    ///
+    /// When this state is `None`, the derived state is `None` as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The function computing the derived value from the source value.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the derived `ManagedState<U>`.
+    fn map<U, F>(&self, f: F) -> ManagedState<U>
+        where
+        U: Send + Sync + 'static,
+        F: Fn(&T) -> U + Send + Sync + 'static;
+}
+
+impl<T> Derive<T> for ManagedState<T>
+where T: Clone + Send + Sync + 'static
+{
+    fn map<U, F>(&self, f: F) -> ManagedState<U>
+        where
+        U: Send + Sync + 'static,
+        F: Fn(&T) -> U + Send + Sync + 'static,
+    {
+        // Регистрируем наблюдателя под блокировкой на чтение, чтобы не пропустить запись
+        let state_guard = lock::read_recovering(self);
+        let derived = ManagedState::configured(state_guard.as_ref().map(&f), None, true, None);
+        let derived_weak = derived.downgrade();
+        let source_weak = Arc::downgrade(self);
+        self.meta().notifier().register(Arc::new(move |_: &Option<T>| {
+            let (Some(derived), Some(source)) = (derived_weak.upgrade(), source_weak.upgrade()) else {
+                return false;
+            };
+            // Пересчитываем из текущего значения источника, а не из уведомления
+            let value = lock::read_recovering(&source).as_ref().map(&f);
+            let _ = derived.set(value);
+            true
+        }));
+        derived
+    }
+}
+
+/// Creates a state holding the values of two states as a tuple.
+///
+/// The combined state is updated whenever either source changes. It holds `Some((a, b))`
+/// only while both sources hold a value, and `None` otherwise. Like the states created by
+/// `Derive::map`, it is referenced weakly by the sources and recomputed from their current
+/// values, so concurrent writes to the sources cannot leave it holding an outdated pair.
+///
+/// # Arguments
+///
+/// * `a` - The state providing the first element of the tuple.
+/// * `b` - The state providing the second element of the tuple.
+///
+/// # Return Value
+///
+/// Returns the combined `ManagedState<(A, B)>`.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{combine, Getter, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (name, _) = Buffer::new_state(Some(String::from("answer")));
+/// let (value, set_value) = Buffer::new_state(None);
+/// let combined = combine(&name, &value);
+/// assert_eq!(combined.get(), None);
+///
+/// set_value(Some(42)).unwrap();
+/// assert_eq!(combined.get(), Some((String::from("answer"), 42)));
+/// ```
+pub fn combine<A, B>(a: &ManagedState<A>, b: &ManagedState<B>) -> ManagedState<(A, B)>
+    where
+    A: Clone + Send + Sync + 'static,
+    B: Clone + Send + Sync + 'static,
+{
+    let combined = ManagedState::configured(None, None, true, None);
+    let recompute: Arc<dyn Fn() -> bool + Send + Sync> = {
+        let (a, b, combined) = (Arc::downgrade(a), Arc::downgrade(b), combined.downgrade());
+        // Последние значения обоих источников: пересчёт выполняется под этой блокировкой
+        // по текущим значениям источников, поэтому параллельные изменения источников
+        // не перезаписывают друг друга устаревшими парами
+        let latest = lock::RwLock::new((None::<A>, None::<B>));
+        Arc::new(move || {
+            let Some(combined) = combined.upgrade() else {
+                return false;
+            };
+            let mut latest = lock::write_recovering(&latest);
+            // Удалённый источник больше не меняется, так что его последнее значение остаётся в силе.
+            // Источники читаются по отдельности: удержание обеих блокировок сразу могло бы
+            // привести к взаимоблокировке с вызовом `combine(b, a)` в другом потоке
+            if let Some(a) = a.upgrade() {
+                latest.0 = lock::read_recovering(&a).clone();
+            }
+            if let Some(b) = b.upgrade() {
+                latest.1 = lock::read_recovering(&b).clone();
+            }
+            let _ = combined.set(zip(&latest));
+            true
+        })
+    };
+
+    let recompute_for_a = recompute.clone();
+    a.meta().notifier().register(Arc::new(move |_: &Option<A>| recompute_for_a()));
+    let recompute_for_b = recompute.clone();
+    b.meta().notifier().register(Arc::new(move |_: &Option<B>| recompute_for_b()));
+    // Наблюдатели уже зарегистрированы, так что записи после этого чтения не будут пропущены
+    recompute();
+    combined
+}
+
+/// Converts a state into an independent state of another type, transforming its value once.
+///
+/// Unlike `Derive::map`, the new state is not kept in sync: later writes to `state` do not
+/// affect it. It is a plain state with its own setter, like one created by
+/// `StateManager::new_state`. An empty `state` yields an empty state without calling `f`.
+///
+/// # Arguments
+///
+/// * `state` - The state to convert.
+/// * `f` - The function converting the current value.
+///
+/// # Return Value
+///
+/// Returns a tuple of the new `ManagedState<B>` and a function for modifying it, or a `StateError`
+/// if the current value could not be read.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{map_state, Getter, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (count, _) = Buffer::new_state(Some(42));
+/// let (text, _) = map_state(count, |count| count.to_string()).unwrap();
+/// assert_eq!(text.get(), Some(String::from("42")));
+/// ```
+pub fn map_state<A, B, F>(state: ManagedState<A>, f: F) -> error::Result<(ManagedState<B>, StateSetter<B>)>
+    where
+    A: Clone,
+    B: Send + Sync + 'static,
+    F: FnOnce(A) -> B,
+{
+    let mapped = ManagedState::configured(state.try_get_result()?.map(f), None, true, None);
+    let setter = mapped.setter();
+    Ok((mapped, setter))
+}
+
+/// Reads the values of two states as a tuple.
+///
+/// This is the one-off, read-side counterpart of `combine`. Each state is locked for reading on
+/// its own, one after the other, so the pair is not an atomic snapshot: use
+/// `snapshot_consistent` when the two reads must not be separated by a write.
+///
+/// # Arguments
+///
+/// * `a` - The state providing the first element of the tuple.
+/// * `b` - The state providing the second element of the tuple.
+///
+/// # Return Value
+///
+/// Returns `Some((a, b))` if both states hold a value, or `None` if either of them is empty or
+/// poisoned.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{zip_get, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (name, _) = Buffer::new_state(Some(String::from("answer")));
+/// let (value, _) = Buffer::new_state(Some(42));
+/// assert_eq!(zip_get(&name, &value), Some((String::from("answer"), 42)));
+/// ```
+pub fn zip_get<A: Clone, B: Clone>(a: &State<A>, b: &State<B>) -> Option<(A, B)> {
+    Some((a.get()?, b.get()?))
+}
+
+fn zip<A: Clone, B: Clone>(latest: &(Option<A>, Option<B>)) -> Option<(A, B)> {
+    match latest {
+        (Some(a), Some(b)) => Some((a.clone(), b.clone())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::tests::S;
+    use crate::{combine, map_state, zip_get, Derive, Getter, StateManager, Updater};
+
+    #[test]
+    fn test_map_to_string() {
+        let (state, set_state) = S::new_state(Some(1));
+        let text = state.map(|value| value.to_string());
+        assert_eq!(text.get(), Some(String::from("1")));
+
+        set_state(Some(42)).unwrap();
+        assert_eq!(text.get(), Some(String::from("42")));
+    }
+
+    #[test]
+    fn test_map_none_source() {
+        let (state, set_state) = S::new_state(None::<i32>);
+        let text = state.map(|value| value.to_string());
+        assert_eq!(text.get(), None);
+
+        set_state(Some(1)).unwrap();
+        assert_eq!(text.get(), Some(String::from("1")));
+        set_state(None).unwrap();
+        assert_eq!(text.get(), None);
+    }
+
+    #[test]
+    fn test_map_follows_updates() {
+        let (state, _) = S::new_state(Some(1));
+        let doubled = state.map(|value| value * 2);
+        state.update(|value| *value = value.map(|v| v + 1)).unwrap();
+        assert_eq!(doubled.get(), Some(4));
+    }
+
+    #[test]
+    fn test_map_chain() {
+        let (state, set_state) = S::new_state(Some(2));
+        let squared = state.map(|value| value * value);
+        let text = squared.map(|value| format!("{}!", value));
+
+        set_state(Some(3)).unwrap();
+        assert_eq!(text.get(), Some(String::from("9!")));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_map_with_concurrent_writers() {
+        use std::thread;
+
+        let (state, set_state) = S::new_state(Some(0));
+        let doubled = state.map(|value| value * 2);
+        let writers: Vec<_> = (0..8).map(|thread_index| {
+            let set_state = set_state.clone();
+            thread::spawn(move || {
+                for i in 0..200 {
+                    set_state(Some(thread_index * 1_000 + i)).unwrap();
+                }
+            })
+        }).collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        assert_eq!(doubled.get(), state.get().map(|value| value * 2));
+    }
+
+    #[test]
+    fn test_dropped_derived_state_is_not_updated() {
+        let (state, set_state) = S::new_state(Some(1));
+        let derived = state.map(|value| value + 1);
+        drop(derived);
+        // Источник не удерживает производное состояние => запись проходит без ошибок
+        set_state(Some(2)).unwrap();
+        assert_eq!(state.get(), Some(2));
+    }
+
+    #[test]
+    fn test_combine_only_when_both_present() {
+        let (a, set_a) = S::new_state(None::<i32>);
+        let (b, set_b) = S::new_state(None::<String>);
+        let combined = combine(&a, &b);
+        assert_eq!(combined.get(), None);
+
+        set_a(Some(1)).unwrap();
+        assert_eq!(combined.get(), None);
+
+        set_b(Some(String::from("one"))).unwrap();
+        assert_eq!(combined.get(), Some((1, String::from("one"))));
+
+        set_a(Some(2)).unwrap();
+        assert_eq!(combined.get(), Some((2, String::from("one"))));
+
+        // Если один из источников пуст, объединённое значение тоже пусто
+        set_b(None).unwrap();
+        assert_eq!(combined.get(), None);
+    }
+
+    #[test]
+    fn test_combine_initial_values() {
+        let (a, _) = S::new_state(Some(1));
+        let (b, _) = S::new_state(Some(2));
+        let combined = combine(&a, &b);
+        assert_eq!(combined.get(), Some((1, 2)));
+    }
+
+    #[test]
+    fn test_combine_same_state() {
+        let (a, set_a) = S::new_state(Some(1));
+        let combined = combine(&a, &a);
+        set_a(Some(2)).unwrap();
+        assert_eq!(combined.get(), Some((2, 2)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_combine_with_concurrent_writers() {
+        use std::thread;
+
+        let (a, set_a) = S::new_state(Some(0));
+        let (b, set_b) = S::new_state(Some(0));
+        let combined = combine(&a, &b);
+        let writers: Vec<_> = (0..8).map(|thread_index| {
+            let (set_a, set_b) = (set_a.clone(), set_b.clone());
+            thread::spawn(move || {
+                for i in 0..200 {
+                    // половина потоков пишет в `a`, другая половина в `b`
+                    let setter = if thread_index % 2 == 0 { &set_a } else { &set_b };
+                    setter(Some(thread_index * 1_000 + i)).unwrap();
+                }
+            })
+        }).collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        assert_eq!(combined.get(), zip_get(&a, &b));
+    }
+
+    #[test]
+    fn test_map_state_to_string() {
+        let (count, set_count) = S::new_state(Some(7));
+        let (text, set_text) = map_state(count.clone(), |count| format!("#{}", count)).unwrap();
+        assert_eq!(text.get(), Some(String::from("#7")));
+
+        // состояния независимы друг от друга
+        set_count(Some(8)).unwrap();
+        assert_eq!(text.get(), Some(String::from("#7")));
+        set_text(Some(String::from("#9"))).unwrap();
+        assert_eq!(count.get(), Some(8));
+        assert_eq!(text.get(), Some(String::from("#9")));
+    }
+
+    #[test]
+    fn test_map_state_empty_source() {
+        let (count, _) = S::new_state(None::<i32>);
+        let (text, _) = map_state(count, |_| -> String { panic!("must not be called") }).unwrap();
+        assert_eq!(text.get(), None);
+    }
+
+    #[test]
+    fn test_zip_get_both_present() {
+        let (a, _) = S::new_state(Some(1));
+        let (b, _) = S::new_state(Some("one"));
+        assert_eq!(zip_get(&a, &b), Some((1, "one")));
+    }
+
+    #[test]
+    fn test_zip_get_one_absent() {
+        let (a, _) = S::new_state(Some(1));
+        let (b, _) = S::new_state(None::<&str>);
+        assert_eq!(zip_get(&a, &b), None);
+        assert_eq!(zip_get(&b, &a), None);
+    }
+
+    #[test]
+    fn test_zip_get_both_absent() {
+        let (a, _) = S::new_state(None::<i32>);
+        let (b, _) = S::new_state(None::<&str>);
+        assert_eq!(zip_get(&a, &b), None);
+    }
+}
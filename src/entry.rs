@@ -0,0 +1,150 @@
+use crate::lock::RwLockWriteGuard;
+use crate::meta::Meta;
+
+/// A view into the value of a state, holding its write lock.
+///
+/// Returned by `Access::entry`. Like `HashMap::entry`, it lets the caller compose conditional
+/// modifications which all happen under a single lock acquisition. The lock is released when the
+/// entry is dropped; if any of its methods changed the value, the observers are notified then.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Access, Getter, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (visits, _) = Buffer::new_state(None);
+/// for _ in 0..3 {
+///     visits.entry().unwrap().and_modify(|count| *count += 1).or_insert(1);
+/// }
+/// assert_eq!(visits.get(), Some(3));
+/// ```
+pub struct StateEntry<'a, T> {
+    meta: Option<&'a Meta<T>>,
+    state_guard: Option<RwLockWriteGuard<'a, Option<T>>>,
+    modified: bool,
+}
+
+impl<'a, T> StateEntry<'a, T> {
+    /// Creates an entry from the write guard of the state described by `meta`, if it has any.
+    pub(crate) fn new(meta: Option<&'a Meta<T>>, state_guard: RwLockWriteGuard<'a, Option<T>>) -> Self {
+        StateEntry {
+            meta,
+            state_guard: Some(state_guard),
+            modified: false,
+        }
+    }
+
+    fn value(&mut self) -> &mut Option<T> {
+        // Страж удаляется только в drop
+        self.state_guard.as_mut().expect("the entry holds the lock until it is dropped")
+    }
+
+    /// Stores `value` if the state is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to store.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the entry, so further calls can be chained.
+    pub fn or_insert(self, value: T) -> Self {
+        self.or_insert_with(|| value)
+    }
+
+    /// Stores the result of `f` if the state is empty. `f` is not called otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The function producing the value to store.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the entry, so further calls can be chained.
+    pub fn or_insert_with<F: FnOnce() -> T>(mut self, f: F) -> Self {
+        if self.value().is_none() {
+            *self.value() = Some(f());
+            self.modified = true;
+        }
+        self
+    }
+
+    /// Applies `f` to the value if the state holds one. `f` is not called otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure modifying the value in place.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the entry, so further calls can be chained.
+    pub fn and_modify<F: FnOnce(&mut T)>(mut self, f: F) -> Self {
+        if let Some(value) = self.value().as_mut() {
+            f(value);
+            self.modified = true;
+        }
+        self
+    }
+
+    /// Returns a reference to the current value, or `None` if the state is empty.
+    pub fn get(&self) -> Option<&T> {
+        self.state_guard.as_ref().and_then(|state_guard| state_guard.as_ref())
+    }
+}
+
+impl<T> Drop for StateEntry<'_, T> {
+    fn drop(&mut self) {
+        if let Some(state_guard) = self.state_guard.take() {
+            match self.meta {
+                Some(meta) if self.modified => meta.publish(state_guard),
+                _ => drop(state_guard),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::tests::S;
+    #[cfg(feature = "std")]
+    use crate::Observable;
+    use crate::{Access, Getter, StateManager, Versioned};
+
+    #[test]
+    fn test_entry_or_insert_and_modify() {
+        let (state, _) = S::new_state(None);
+        // Пустое состояние => вставка, затем изменение
+        let entry = state.entry().unwrap().or_insert(5).and_modify(|v| *v += 1);
+        assert_eq!(entry.get(), Some(&6));
+        drop(entry);
+        assert_eq!(state.get(), Some(6));
+
+        // Значение уже есть => or_insert_with не вызывается
+        state.entry().unwrap()
+            .or_insert_with(|| unreachable!())
+            .and_modify(|v| *v *= 10);
+        assert_eq!(state.get(), Some(60));
+    }
+
+    #[test]
+    fn test_entry_without_changes_does_not_publish() {
+        let (state, _) = S::new_state(Some(1));
+        let version = state.version();
+        state.entry().unwrap().or_insert(2);
+        assert_eq!(state.version(), version);
+        assert_eq!(state.get(), Some(1));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_entry_notifies_once() {
+        let (state, _) = S::new_state(None);
+        let receiver = state.subscribe();
+        state.entry().unwrap().or_insert(1).and_modify(|v| *v += 1);
+        assert_eq!(receiver.try_recv().unwrap(), Some(2));
+        assert!(receiver.try_recv().is_err());
+    }
+}
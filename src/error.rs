@@ -1,25 +1,324 @@
-use std::fmt::{self, Debug};
+use alloc::string::String;
+use core::fmt::{self, Debug};
+use core::time::Duration;
+#[cfg(feature = "std")]
 use std::error::Error;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::sync::{Arc, PoisonError};
 
-pub type Result<T> = std::result::Result<T, StateError>;
+pub type Result<T> = core::result::Result<T, StateError>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum StateError
 {
     MismatchedTypes(),
+    /// The lock protecting the state was poisoned by a thread that panicked while holding it.
+    Poisoned,
+    /// The lock protecting the state could not be acquired within the given duration.
+    Timeout(Duration),
+    /// An I/O operation (e.g. reading or writing a snapshot file) failed.
+    /// The underlying error is available through `Error::source` and is not repeated in the
+    /// message.
+    #[cfg(feature = "std")]
+    Io(Arc<io::Error>),
+    /// The state could not be serialized or deserialized.
+    /// Like `Io`, the underlying error is available through `Error::source` and is not repeated
+    /// in the message.
+    #[cfg(feature = "std")]
+    Serde(Arc<dyn Error + Send + Sync>),
+    /// A value was rejected by the validator of the state.
+    Validation(String),
+    /// The operation requires the state to hold a value, but it is empty.
+    Empty,
+    /// The state could not be taken apart because it is still referenced by the given number
+    /// of other clones or setters.
+    Shared(usize),
+    /// The state was frozen and no longer accepts writes.
+    Frozen,
+    /// An error of the user, e.g. a domain error raised by a middleware. The original error is
+    /// available through `Error::source`, is not repeated in the message, and can be recovered
+    /// with `downcast_ref`.
+    ///
+    /// It is kept in an `Arc` rather than a `Box<dyn Error + Send + Sync>` because `StateError`
+    /// is `Clone`, and an arbitrary error cannot be cloned, only shared between the clones.
+    #[cfg(feature = "std")]
+    Custom(Arc<dyn Error + Send + Sync>),
     Default(String),
 }
 
+#[cfg(feature = "std")]
+impl StateError {
+    /// Wraps an arbitrary error into `StateError::Custom`.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The error to wrap.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the `Custom` variant holding `error`.
+    pub fn custom<E: Error + Send + Sync + 'static>(error: E) -> Self {
+        StateError::Custom(Arc::new(error))
+    }
+}
+
 impl fmt::Display for StateError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             StateError::MismatchedTypes() => {
                 write!(f, "Given object has different type")
             },
+            StateError::Poisoned => {
+                write!(f, "State lock is poisoned: another thread panicked while holding it")
+            },
+            StateError::Timeout(duration) => {
+                write!(f, "Timed out after {:?} waiting for the state lock", duration)
+            },
+            #[cfg(feature = "std")]
+            StateError::Io(_) => write!(f, "I/O error"),
+            #[cfg(feature = "std")]
+            StateError::Serde(_) => write!(f, "Serialization error"),
+            StateError::Validation(s) => write!(f, "Validation failed: {}", s),
+            StateError::Empty => write!(f, "The operation requires a value, but the state is empty"),
+            StateError::Shared(count) => {
+                write!(f, "The state is still referenced by {} other clones or setters", count)
+            },
+            StateError::Frozen => write!(f, "The state is frozen and cannot be written"),
+            #[cfg(feature = "std")]
+            StateError::Custom(_) => write!(f, "custom error"),
             StateError::Default(s) => write!(f, "{}", s)
         }
     }
 }
 
-impl Error for StateError {}
+#[cfg(feature = "std")]
+impl Error for StateError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StateError::Io(e) => Some(e.as_ref()),
+            StateError::Serde(e) => Some(e.as_ref()),
+            StateError::Custom(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// `std::io::Error` is not comparable, so two `Io` errors are equal when they have the same
+/// kind and message, and two `Serde` errors when they have the same message. Two `Custom` errors are equal only when they share the same error, i.e. one
+/// is a clone of the other.
+impl PartialEq for StateError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (StateError::MismatchedTypes(), StateError::MismatchedTypes()) => true,
+            (StateError::Poisoned, StateError::Poisoned) => true,
+            (StateError::Timeout(a), StateError::Timeout(b)) => a == b,
+            #[cfg(feature = "std")]
+            (StateError::Io(a), StateError::Io(b)) => {
+                a.kind() == b.kind() && a.to_string() == b.to_string()
+            },
+            #[cfg(feature = "std")]
+            (StateError::Serde(a), StateError::Serde(b)) => a.to_string() == b.to_string(),
+            (StateError::Validation(a), StateError::Validation(b)) => a == b,
+            (StateError::Empty, StateError::Empty) => true,
+            (StateError::Shared(a), StateError::Shared(b)) => a == b,
+            (StateError::Frozen, StateError::Frozen) => true,
+            #[cfg(feature = "std")]
+            (StateError::Custom(a), StateError::Custom(b)) => Arc::ptr_eq(a, b),
+            (StateError::Default(a), StateError::Default(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for StateError {}
+
+/// Wraps a message into `StateError::Default`, so closures can write `Err("message".into())`.
+impl From<String> for StateError {
+    fn from(message: String) -> Self {
+        StateError::Default(message)
+    }
+}
+
+impl From<&str> for StateError {
+    fn from(message: &str) -> Self {
+        StateError::Default(String::from(message))
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for StateError {
+    fn from(e: io::Error) -> Self {
+        StateError::Io(Arc::new(e))
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for StateError {
+    fn from(e: serde_json::Error) -> Self {
+        StateError::Serde(Arc::new(e))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<G> From<PoisonError<G>> for StateError {
+    fn from(_: PoisonError<G>) -> Self {
+        StateError::Poisoned
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #[cfg(feature = "std")]
+    use std::error::Error;
+    #[cfg(feature = "std")]
+    use std::sync::{Arc, RwLock};
+    #[cfg(feature = "std")]
+    use std::thread;
+    use std::time::Duration;
+
+    use super::{Result, StateError};
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_poison_error_conversion() {
+        let lock = Arc::new(RwLock::new(0));
+        let lock_for_panic = lock.clone();
+        let _ = thread::spawn(move || {
+            let _guard = lock_for_panic.write().unwrap();
+            panic!("poison the lock");
+        }).join();
 
+        let err: StateError = lock.read().unwrap_err().into();
+        assert!(matches!(err, StateError::Poisoned));
+    }
+
+    #[test]
+    fn test_poisoned_display() {
+        assert_eq!(
+            StateError::Poisoned.to_string(),
+            "State lock is poisoned: another thread panicked while holding it"
+        );
+    }
+
+    #[test]
+    fn test_timeout_display() {
+        let err = StateError::Timeout(Duration::from_millis(250));
+        assert_eq!(err.to_string(), "Timed out after 250ms waiting for the state lock");
+
+        // Вариант остаётся полноценной ошибкой
+        #[cfg(feature = "std")]
+        {
+            let err: Box<dyn Error> = Box::new(err);
+            assert!(err.source().is_none());
+        }
+    }
+
+    #[test]
+    fn test_equality() {
+        assert_eq!(StateError::MismatchedTypes(), StateError::MismatchedTypes());
+        assert_eq!(StateError::Default(String::from("x")), StateError::Default(String::from("x")));
+        assert_ne!(StateError::Default(String::from("x")), StateError::Default(String::from("y")));
+        assert_ne!(StateError::Poisoned, StateError::MismatchedTypes());
+    }
+
+    #[test]
+    fn test_clone() {
+        let err = StateError::Default(String::from("x"));
+        assert_eq!(err.clone(), err);
+        let err = StateError::Timeout(Duration::from_secs(1));
+        assert_eq!(err.clone(), err);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_io_error_source() {
+        let err: StateError = std::io::Error::new(std::io::ErrorKind::NotFound, "missing").into();
+        assert_eq!(err.to_string(), "I/O error");
+
+        let source = err.source().unwrap();
+        let io_err = source.downcast_ref::<std::io::Error>().unwrap();
+        assert_eq!(io_err.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_io_error_equality() {
+        let a: StateError = std::io::Error::new(std::io::ErrorKind::NotFound, "missing").into();
+        let b: StateError = std::io::Error::new(std::io::ErrorKind::NotFound, "missing").into();
+        let c: StateError = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "missing").into();
+        assert_eq!(a, b);
+        assert_eq!(a.clone(), a);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_validation_display() {
+        let err = StateError::Validation(String::from("age must be positive"));
+        assert_eq!(err.to_string(), "Validation failed: age must be positive");
+        assert_eq!(err.clone(), err);
+        assert_ne!(err, StateError::Default(String::from("age must be positive")));
+    }
+
+    #[test]
+    fn test_empty_display() {
+        assert_eq!(StateError::Empty.to_string(), "The operation requires a value, but the state is empty");
+        assert_eq!(StateError::Empty, StateError::Empty);
+        assert_ne!(StateError::Empty, StateError::Poisoned);
+    }
+
+    #[test]
+    fn test_shared_display() {
+        assert_eq!(StateError::Shared(2).to_string(), "The state is still referenced by 2 other clones or setters");
+        assert_ne!(StateError::Shared(1), StateError::Shared(2));
+    }
+
+    #[test]
+    fn test_frozen_display() {
+        assert_eq!(StateError::Frozen.to_string(), "The state is frozen and cannot be written");
+        assert_eq!(StateError::Frozen, StateError::Frozen);
+    }
+
+    #[test]
+    fn test_from_message() {
+        let check = |value: i32| -> Result<i32> {
+            if value < 0 {
+                return Err("value must not be negative".into());
+            }
+            if value > 100 {
+                return Err(format!("value {} is too large", value).into());
+            }
+            Ok(value)
+        };
+        assert_eq!(check(1), Ok(1));
+        assert_eq!(check(-1), Err(StateError::Default(String::from("value must not be negative"))));
+        assert_eq!(check(101), Err(StateError::Default(String::from("value 101 is too large"))));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_custom_error_source() {
+        #[derive(Debug)]
+        struct OutOfStock {
+            item: &'static str,
+        }
+        impl std::fmt::Display for OutOfStock {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "{} is out of stock", self.item)
+            }
+        }
+        impl Error for OutOfStock {}
+
+        let err = StateError::custom(OutOfStock { item: "apple" });
+        assert_eq!(err.to_string(), "custom error");
+        assert_eq!(err.clone(), err);
+        assert_ne!(err, StateError::custom(OutOfStock { item: "apple" }));
+        assert_ne!(err, StateError::Default(String::from("apple is out of stock")));
+
+        // исходная ошибка доступна через source
+        let source = err.source().unwrap();
+        assert_eq!(source.downcast_ref::<OutOfStock>().unwrap().item, "apple");
+        assert_eq!(source.to_string(), "apple is out of stock");
+    }
+}
@@ -0,0 +1,256 @@
+use crate::{error, lock, Getter, ManagedState, State};
+
+/// A read-only view of a frozen state.
+///
+/// A `FrozenState` is produced by `freeze` and only implements `Getter`: it offers no way of
+/// modifying the value. Every write to the state made through the crate, whether by a setter
+/// created before freezing or by a method of `Setter`, `Swapper`, `Updater`, `History`,
+/// `Access`, `VecState` or `with_locked`, returns `StateError::Frozen`, so the value is
+/// guaranteed not to change. Clones of the frozen state share the value.
+pub struct FrozenState<T> {
+    state: State<T>,
+}
+
+impl<T> Clone for FrozenState<T> {
+    fn clone(&self) -> Self {
+        FrozenState {
+            state: self.state.clone(),
+        }
+    }
+}
+
+/// Freezes a state, making its value immutable.
+///
+/// # Arguments
+///
+/// * `state` - The state to freeze.
+///
+/// # Return Value
+///
+/// Returns a `FrozenState<T>` providing read-only access to the value. From now on, every write
+/// to the state returns `StateError::Frozen`.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{error::StateError, freeze, Getter, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (config, set_config) = Buffer::new_state(Some(String::from("production")));
+/// let config = freeze(config);
+///
+/// assert_eq!(config.get(), Some(String::from("production")));
+/// assert_eq!(set_config(None), Err(StateError::Frozen));
+/// ```
+pub fn freeze<T>(state: ManagedState<T>) -> FrozenState<T> {
+    state.freeze();
+    FrozenState { state: state.into_state() }
+}
+
+/// The `Freeze` trait freezes a state in place, without replacing it by a `FrozenState`.
+///
+/// This suits states which are still passed around as `ManagedState<T>`, e.g. a configuration
+/// which becomes read-only once the application has started. Like `freeze`, it makes every write
+/// to the state fail with `StateError::Frozen`: the setters created before freezing, as well as
+/// the methods of `Setter`, `Swapper`, `Updater`, `History`, `Access` and `VecState`.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{error::StateError, Freeze, StateBuffer, StateManager, Swapper};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, set_state) = Buffer::new_state(Some(1));
+/// set_state(Some(2)).unwrap();
+///
+/// state.freeze();
+/// assert!(state.is_frozen());
+/// assert_eq!(set_state(Some(3)), Err(StateError::Frozen));
+/// assert_eq!(state.swap(Some(3)), Err(StateError::Frozen));
+/// ```
+pub trait Freeze {
+    /// Makes every further write to the state fail with `StateError::Frozen`.
+    ///
+    /// Waits for a write in progress to complete. Freezing a frozen state has no effect.
+    fn freeze(&self);
+
+    /// Returns `true` if the state is frozen.
+    fn is_frozen(&self) -> bool;
+}
+
+impl<T> Freeze for ManagedState<T> {
+    fn freeze(&self) {
+        let state_guard = lock::write_recovering(self);
+        self.meta().freeze();
+        drop(state_guard);
+    }
+
+    fn is_frozen(&self) -> bool {
+        self.meta().is_frozen()
+    }
+}
+
+impl<T> Getter<T> for FrozenState<T>
+where T: Clone
+{
+    fn get(&self) -> Option<T> {
+        self.state.get()
+    }
+
+    fn try_get(&self) -> Option<T> {
+        self.state.try_get()
+    }
+
+    fn try_get_result(&self) -> error::Result<Option<T>> {
+        self.state.try_get_result()
+    }
+
+    fn get_or(&self, default: T) -> T {
+        self.state.get_or(default)
+    }
+
+    fn get_or_default(&self) -> T
+        where T: Default
+    {
+        self.state.get_or_default()
+    }
+
+    fn get_or_else<F: FnOnce() -> T>(&self, f: F) -> T {
+        self.state.get_or_else(f)
+    }
+
+    fn filter_get<F: Fn(&T) -> bool>(&self, pred: F) -> Option<T> {
+        self.state.filter_get(pred)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::error::StateError;
+    use crate::tests::S;
+    use crate::{
+        freeze, with_locked, Access, Freeze, Getter, History, Setter, StateManager, Swapper,
+        Updater, VecState,
+    };
+
+    #[test]
+    fn test_freeze_reads_work_writes_fail() {
+        let (state, set_state) = S::new_state(Some(1));
+        set_state(Some(2)).unwrap();
+
+        let frozen = freeze(state);
+        assert_eq!(frozen.get(), Some(2));
+        assert_eq!(frozen.clone().get_or(0), 2);
+
+        assert_eq!(set_state(Some(3)), Err(StateError::Frozen));
+        // Значение не изменилось
+        assert_eq!(frozen.get(), Some(2));
+    }
+
+    #[test]
+    fn test_freeze_invalidates_other_setters() {
+        let (state, set_state) = S::new_fold_state(0, |total: &mut i32, input: i32| *total += input);
+        let (other, set_other) = S::new_state(Some(String::from("a")));
+        let try_setter = other.clone();
+
+        let frozen = freeze(state);
+        assert_eq!(set_state(1), Err(StateError::Frozen));
+        assert_eq!(frozen.get(), Some(0));
+
+        let _frozen_other = freeze(other);
+        assert_eq!(set_other(None), Err(StateError::Frozen));
+        assert_eq!(
+            try_setter.try_set(Some(String::from("b"))),
+            Err((Some(String::from("b")), StateError::Frozen))
+        );
+        assert_eq!(try_setter.set_recovering(None), Err(StateError::Frozen));
+        assert_eq!(try_setter.get(), Some(String::from("a")));
+    }
+
+    #[test]
+    fn test_freeze_rejects_every_write_path() {
+        let (state, set_state) = S::new_history_state(Some(1), 4);
+        let (other, _) = S::new_state(Some(2));
+        set_state(Some(3)).unwrap();
+        let frozen = freeze(state.clone());
+
+        assert_eq!(state.swap(Some(4)), Err(StateError::Frozen));
+        assert_eq!(state.take(), Err(StateError::Frozen));
+        assert_eq!(state.compare_and_swap(Some(3), Some(4)), Err(StateError::Frozen));
+        assert_eq!(state.replace(4), Err(StateError::Frozen));
+        // Обмен не меняет ни одно из состояний, даже если заморожено только одно
+        assert_eq!(state.swap_with(&other), Err(StateError::Frozen));
+        assert_eq!(other.swap_with(&state), Err(StateError::Frozen));
+
+        assert_eq!(state.update(|value| *value = None), Err(StateError::Frozen));
+        assert_eq!(state.batch(|value| *value = None), Err(StateError::Frozen));
+        assert_eq!(state.get_and_update(|_| None), Err(StateError::Frozen));
+        assert_eq!(state.update_and_get(|_| None), Err(StateError::Frozen));
+        assert_eq!(state.partial_update(|value| *value += 1), Err(StateError::Frozen));
+        assert_eq!(state.try_update(|value| *value = None), Err(StateError::Frozen));
+
+        assert_eq!(state.undo(), Err(StateError::Frozen));
+        assert_eq!(state.redo(), Err(StateError::Frozen));
+
+        assert_eq!(state.with_write(|value| *value = None), Err(StateError::Frozen));
+        assert!(matches!(state.entry(), Err(StateError::Frozen)));
+        assert_eq!(with_locked(&[&other, &state], |values| *values[0] = None), Err(StateError::Frozen));
+
+        assert_eq!(state.set_if_none(4), Err(StateError::Frozen));
+        assert_eq!(state.reset(), Err(StateError::Frozen));
+
+        assert_eq!(frozen.get(), Some(3));
+        assert_eq!(other.get(), Some(2));
+
+        let (queue, _) = S::new_state(Some(vec![1]));
+        let _frozen_queue = freeze(queue.clone());
+        assert_eq!(queue.push(2), Err(StateError::Frozen));
+        assert_eq!(queue.drain_vec(), Err(StateError::Frozen));
+        assert_eq!(queue.get(), Some(vec![1]));
+    }
+
+    #[test]
+    fn test_freeze_mid_lifetime() {
+        let (state, set_state) = S::new_state(Some(1));
+        assert!(!state.is_frozen());
+        set_state(Some(2)).unwrap();
+        set_state(Some(3)).unwrap();
+
+        state.freeze();
+        // Повторная заморозка ничего не меняет
+        state.freeze();
+        assert!(state.is_frozen());
+        assert!(state.clone().is_frozen());
+
+        assert_eq!(set_state(Some(4)), Err(StateError::Frozen));
+        assert_eq!(state.get(), Some(3));
+    }
+
+    #[test]
+    fn test_freeze_in_place_rejects_every_write_path() {
+        let (state, set_state) = S::new_history_state(Some(1), 4);
+        set_state(Some(2)).unwrap();
+        state.freeze();
+
+        assert_eq!(state.swap(Some(3)), Err(StateError::Frozen));
+        assert_eq!(state.take(), Err(StateError::Frozen));
+        assert_eq!(state.update(|value| *value = None), Err(StateError::Frozen));
+        assert_eq!(state.partial_update(|value| *value += 1), Err(StateError::Frozen));
+        assert_eq!(state.update_and_get(|_| None), Err(StateError::Frozen));
+        assert_eq!(state.undo(), Err(StateError::Frozen));
+        assert_eq!(state.with_write(|value| *value = None), Err(StateError::Frozen));
+        assert!(matches!(state.entry(), Err(StateError::Frozen)));
+        // Ни одна запись не прошла, в том числе откат по истории
+        assert_eq!(state.get(), Some(2));
+
+        let (queue, _) = S::new_state(Some(vec![1]));
+        queue.freeze();
+        assert_eq!(queue.push(2), Err(StateError::Frozen));
+        assert_eq!(queue.drain_vec(), Err(StateError::Frozen));
+        assert_eq!(queue.get(), Some(vec![1]));
+    }
+}
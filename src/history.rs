@@ -0,0 +1,237 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::{error, lock, ManagedState, StateExt};
+
+/// The bounded log of previous (and undone) values kept by a state created with `StateManager::new_history_state`.
+pub(crate) struct HistoryLog<T> {
+    capacity: usize,
+    undo: VecDeque<Option<T>>,
+    redo: Vec<Option<T>>,
+}
+
+impl<T> HistoryLog<T> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        HistoryLog {
+            capacity,
+            undo: VecDeque::with_capacity(capacity),
+            redo: Vec::new(),
+        }
+    }
+
+    /// Records the value replaced by a write, dropping the oldest entry if the log is full.
+    ///
+    /// The redo stack is cleared separately, when the write is published.
+    pub(crate) fn record(&mut self, previous: Option<T>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.undo.len() == self.capacity {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(previous);
+    }
+
+    /// Drops the values rolled back by `undo`, which a new write invalidates.
+    pub(crate) fn clear_redo(&mut self) {
+        self.redo.clear();
+    }
+}
+
+/// The `History` trait provides rolling back and re-applying the changes made by the setter.
+///
+/// Only states created with `StateManager::new_history_state` keep a history; for any other
+/// state there is nothing to undo. Writes made through the other traits of this crate
+/// (e.g. `Updater` or `Swapper`) are not recorded.
+///
+/// Undone values can be re-applied with `redo` until the next write, which clears them,
+/// following the usual editor semantics. Every write counts, whether it is made by the setter
+/// or by the other traits of this crate.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Getter, History, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, set_state) = Buffer::new_history_state(Some(1), 10);
+/// set_state(Some(2)).unwrap();
+/// assert!(state.undo().unwrap());
+/// assert_eq!(state.get(), Some(1));
+/// assert!(!state.undo().unwrap());
+/// ```
+pub trait History<T> {
+    /// Restores the value the state held before the last recorded set.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(true)` if a value was restored, `Ok(false)` if the history is empty,
+    /// or a `StateError` if a lock could not be acquired.
+    fn undo(&self) -> error::Result<bool>;
+
+    /// Re-applies the value most recently rolled back by `undo`.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(true)` if a value was re-applied, `Ok(false)` if there is nothing to redo,
+    /// or a `StateError` if a lock could not be acquired.
+    fn redo(&self) -> error::Result<bool>;
+}
+
+impl<T> History<T> for ManagedState<T> {
+    fn undo(&self) -> error::Result<bool> {
+        let meta = self.meta();
+        let Some(history) = meta.history() else {
+            return Ok(false);
+        };
+        let mut state_guard = self.write_for_setter()?;
+        let mut history = lock::write(history)?;
+        let Some(previous) = history.undo.pop_back() else {
+            return Ok(false);
+        };
+        let current = mem::replace(&mut *state_guard, previous);
+        history.redo.push(current);
+        drop(history);
+        meta.publish_restored(state_guard);
+        Ok(true)
+    }
+
+    fn redo(&self) -> error::Result<bool> {
+        let meta = self.meta();
+        let Some(history) = meta.history() else {
+            return Ok(false);
+        };
+        let mut state_guard = self.write_for_setter()?;
+        let mut history = lock::write(history)?;
+        let Some(next) = history.redo.pop() else {
+            return Ok(false);
+        };
+        let current = mem::replace(&mut *state_guard, next);
+        history.record(current);
+        drop(history);
+        meta.publish_restored(state_guard);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::tests::S;
+    use crate::{Access, Getter, History, StateManager, Swapper, Updater};
+
+    #[test]
+    fn test_undo_down_to_initial_value() {
+        let (state, set_state) = S::new_history_state(Some(0), 10);
+        for value in 1..=3 {
+            set_state(Some(value)).unwrap();
+        }
+
+        for expected in (0..3).rev() {
+            assert!(state.undo().unwrap());
+            assert_eq!(state.get(), Some(expected));
+        }
+        assert!(!state.undo().unwrap());
+        assert_eq!(state.get(), Some(0));
+    }
+
+    #[test]
+    fn test_undo_empty_history() {
+        let (state, _) = S::new_history_state(Some(0), 10);
+        assert!(!state.undo().unwrap());
+        assert_eq!(state.get(), Some(0));
+    }
+
+    #[test]
+    fn test_undo_without_history() {
+        let (state, set_state) = S::new_state(Some(0));
+        set_state(Some(1)).unwrap();
+        assert!(!state.undo().unwrap());
+        assert_eq!(state.get(), Some(1));
+    }
+
+    #[test]
+    fn test_history_capacity_drops_oldest() {
+        let (state, set_state) = S::new_history_state(None, 2);
+        for value in 1..=4 {
+            set_state(Some(value)).unwrap();
+        }
+
+        // Сохранены только два последних предыдущих значения: 3 и 2
+        assert!(state.undo().unwrap());
+        assert_eq!(state.get(), Some(3));
+        assert!(state.undo().unwrap());
+        assert_eq!(state.get(), Some(2));
+        assert!(!state.undo().unwrap());
+    }
+
+    #[test]
+    fn test_undo_restores_none() {
+        let (state, set_state) = S::new_history_state(None, 10);
+        set_state(Some(1)).unwrap();
+        assert!(state.undo().unwrap());
+        assert_eq!(state.get(), None);
+    }
+
+    #[test]
+    fn test_redo_after_undo() {
+        let (state, set_state) = S::new_history_state(Some(0), 10);
+        set_state(Some(1)).unwrap();
+        set_state(Some(2)).unwrap();
+        assert!(state.undo().unwrap());
+        assert!(state.undo().unwrap());
+        assert_eq!(state.get(), Some(0));
+
+        assert!(state.redo().unwrap());
+        assert_eq!(state.get(), Some(1));
+
+        // Новая запись очищает стек повтора
+        set_state(Some(5)).unwrap();
+        assert!(!state.redo().unwrap());
+        assert_eq!(state.get(), Some(5));
+        assert!(state.undo().unwrap());
+        assert_eq!(state.get(), Some(1));
+    }
+
+    #[test]
+    fn test_other_writes_clear_redo() {
+        let (state, set_state) = S::new_history_state(Some(0), 10);
+        set_state(Some(1)).unwrap();
+        assert!(state.undo().unwrap());
+
+        // Запись через Updater не записывается в историю, но тоже очищает стек повтора
+        state.update(|value| *value = Some(7)).unwrap();
+        assert_eq!(state.redo(), Ok(false));
+        assert_eq!(state.get(), Some(7));
+
+        set_state(Some(2)).unwrap();
+        assert!(state.undo().unwrap());
+        state.swap(Some(8)).unwrap();
+        assert_eq!(state.redo(), Ok(false));
+
+        set_state(Some(3)).unwrap();
+        assert!(state.undo().unwrap());
+        state.entry().unwrap().and_modify(|value| *value += 1);
+        assert_eq!(state.redo(), Ok(false));
+    }
+
+    #[test]
+    fn test_redo_everything_undone() {
+        let (state, set_state) = S::new_history_state(None, 10);
+        set_state(Some(1)).unwrap();
+        set_state(Some(2)).unwrap();
+        while state.undo().unwrap() {}
+        while state.redo().unwrap() {}
+        assert_eq!(state.get(), Some(2));
+    }
+
+    #[test]
+    fn test_redo_empty() {
+        let (state, _) = S::new_history_state(Some(0), 10);
+        assert!(!state.redo().unwrap());
+        let (state, _) = S::new_state(Some(0));
+        assert!(!state.redo().unwrap());
+    }
+}
@@ -0,0 +1,197 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{error, Getter, ManagedState, StateExt, StateSetter};
+
+/// A state wrapper counting the reads and writes made through it.
+///
+/// The counters help to find hot states and contention points. Only accesses made through the
+/// wrapper, its `Getter` implementation and the setters it creates are counted; accesses made
+/// through the wrapped `ManagedState` directly are not. Clones of the wrapper share the counters.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Getter, InstrumentedState, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, _) = Buffer::new_state(Some(1));
+/// let instrumented = InstrumentedState::new(state);
+/// let set_state = instrumented.setter();
+///
+/// set_state(Some(2)).unwrap();
+/// assert_eq!(instrumented.get(), Some(2));
+/// assert_eq!(instrumented.read_count(), 1);
+/// assert_eq!(instrumented.write_count(), 1);
+/// ```
+pub struct InstrumentedState<T> {
+    state: ManagedState<T>,
+    reads: Arc<AtomicU64>,
+    writes: Arc<AtomicU64>,
+}
+
+impl<T> InstrumentedState<T> {
+    /// Wraps `state` with counters starting at zero.
+    pub fn new(state: ManagedState<T>) -> Self {
+        InstrumentedState {
+            state,
+            reads: Arc::new(AtomicU64::new(0)),
+            writes: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Returns the wrapped state. Accesses made through it are not counted.
+    pub fn state(&self) -> &ManagedState<T> {
+        &self.state
+    }
+
+    /// Returns the number of reads made through the wrapper.
+    pub fn read_count(&self) -> u64 {
+        self.reads.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of writes made through the wrapper and its setters.
+    pub fn write_count(&self) -> u64 {
+        self.writes.load(Ordering::Relaxed)
+    }
+
+    /// Writes `data` to the state, counting the write.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The new value of the state.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` once the value is written, or a `StateError` if the lock could not be acquired.
+    pub fn set(&self, data: Option<T>) -> error::Result<()>
+        where T: Send + Sync + 'static
+    {
+        self.writes.fetch_add(1, Ordering::Relaxed);
+        self.state.set(data)
+    }
+
+    /// Returns a `StateSetter` writing to the state, whose writes are counted.
+    pub fn setter(&self) -> StateSetter<T>
+        where T: Send + Sync + 'static
+    {
+        let instrumented = self.clone();
+        Arc::new(move |data: Option<T>| instrumented.set(data))
+    }
+
+    fn count_read(&self) {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl<T> Clone for InstrumentedState<T> {
+    fn clone(&self) -> Self {
+        InstrumentedState {
+            state: self.state.clone(),
+            reads: self.reads.clone(),
+            writes: self.writes.clone(),
+        }
+    }
+}
+
+impl<T> Getter<T> for InstrumentedState<T>
+where T: Clone
+{
+    fn get(&self) -> Option<T> {
+        self.count_read();
+        self.state.get()
+    }
+
+    fn try_get(&self) -> Option<T> {
+        self.count_read();
+        self.state.try_get()
+    }
+
+    fn try_get_result(&self) -> error::Result<Option<T>> {
+        self.count_read();
+        self.state.try_get_result()
+    }
+
+    fn get_or(&self, default: T) -> T {
+        self.get().unwrap_or(default)
+    }
+
+    fn get_or_default(&self) -> T
+        where T: Default
+    {
+        self.get().unwrap_or_default()
+    }
+
+    fn get_or_else<F: FnOnce() -> T>(&self, f: F) -> T {
+        self.get().unwrap_or_else(f)
+    }
+
+    fn filter_get<F: Fn(&T) -> bool>(&self, pred: F) -> Option<T> {
+        self.count_read();
+        self.state.filter_get(pred)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::thread;
+
+    use crate::tests::S;
+    use crate::{Getter, InstrumentedState, StateManager};
+
+    #[test]
+    fn test_counts_reads_and_writes() {
+        let (state, _) = S::new_state(Some(0));
+        let instrumented = InstrumentedState::new(state);
+        let set_state = instrumented.setter();
+
+        for i in 0..5 {
+            set_state(Some(i)).unwrap();
+        }
+        for _ in 0..7 {
+            instrumented.get();
+        }
+        instrumented.set(Some(10)).unwrap();
+
+        assert_eq!(instrumented.read_count(), 7);
+        assert_eq!(instrumented.write_count(), 6);
+        assert_eq!(instrumented.get_or(0), 10);
+    }
+
+    #[test]
+    fn test_direct_access_not_counted() {
+        let (state, set_state) = S::new_state(Some(1));
+        let instrumented = InstrumentedState::new(state);
+        set_state(Some(2)).unwrap();
+        assert_eq!(instrumented.state().get(), Some(2));
+
+        assert_eq!(instrumented.read_count(), 0);
+        assert_eq!(instrumented.write_count(), 0);
+    }
+
+    #[test]
+    fn test_counts_across_threads() {
+        let (state, _) = S::new_state(Some(0));
+        let instrumented = InstrumentedState::new(state);
+        let mut handles = vec![];
+
+        for i in 0..4 {
+            // Клоны обёртки разделяют счётчики
+            let instrumented_clone = instrumented.clone();
+            handles.push(thread::spawn(move || {
+                for _ in 0..10 {
+                    instrumented_clone.set(Some(i)).unwrap();
+                    instrumented_clone.get();
+                }
+            }));
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(instrumented.read_count(), 40);
+        assert_eq!(instrumented.write_count(), 40);
+    }
+}
@@ -0,0 +1,102 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error;
+use crate::StateExt;
+
+/// The `Json` trait reads and writes the value of the state as a JSON string.
+///
+/// This is convenient for debugging and for passing the state across FFI boundaries.
+/// The empty state is represented as `null`.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Getter, Json, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, _) = Buffer::new_state(Some(vec![1, 2]));
+/// assert_eq!(state.get_json().unwrap(), "[1,2]");
+///
+/// state.set_json("[3]").unwrap();
+/// assert_eq!(state.get(), Some(vec![3]));
+/// ```
+pub trait Json<T> {
+    /// Serializes the current value of the state to a JSON string.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the JSON string, or `StateError::Serde` if the value could not be serialized.
+    fn get_json(&self) -> error::Result<String>;
+
+    /// Parses `json` and stores the result in the state, like the setter does.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The JSON representation of the new value, `null` for an empty state.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` once the value is written, `StateError::Serde` if `json` could not be
+    /// parsed, or a `StateError` if the write failed. A value which cannot be parsed leaves the
+    /// state unchanged.
+    fn set_json(&self, json: &str) -> error::Result<()>;
+}
+
+impl<T, S> Json<T> for S
+    where
+    T: Serialize + DeserializeOwned,
+    S: StateExt<T>,
+{
+    fn get_json(&self) -> error::Result<String> {
+        Ok(serde_json::to_string(self.state())?)
+    }
+
+    fn set_json(&self, json: &str) -> error::Result<()> {
+        let data: Option<T> = serde_json::from_str(json)?;
+        self.set(data)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::error::StateError;
+    use crate::tests::S;
+    use crate::{Getter, Json, StateManager};
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct User {
+        name: String,
+        age: u32,
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let (state, _) = S::new_state(Some(User { name: String::from("Ann"), age: 30 }));
+        let json = state.get_json().unwrap();
+        assert_eq!(json, r#"{"name":"Ann","age":30}"#);
+
+        let (copy, _) = S::new_state(None::<User>);
+        copy.set_json(&json).unwrap();
+        assert_eq!(copy.get(), state.get());
+
+        copy.set_json("null").unwrap();
+        assert_eq!(copy.get(), None);
+    }
+
+    #[test]
+    fn test_set_json_invalid() {
+        let (state, _) = S::new_state(Some(User { name: String::from("Ann"), age: 30 }));
+        let err = state.set_json(r#"{"name":"Bob"}"#).unwrap_err();
+        assert!(matches!(err, StateError::Serde(_)));
+        // Исходная ошибка serde_json доступна через source
+        let source = std::error::Error::source(&err).unwrap();
+        assert!(source.downcast_ref::<serde_json::Error>().unwrap().is_data());
+        // Некорректный JSON не меняет состояние
+        assert_eq!(state.get().unwrap().name, "Ann");
+    }
+}
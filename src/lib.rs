@@ -1,16 +1,213 @@
-use std::sync::{Arc, RwLock};
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
+
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::mem;
+
+use lock::{RwLock, RwLockWriteGuard};
+use meta::Meta;
 
 /// Defines the `State` type as an atomically reference-counted read/write lock containing an optional value of type `T`.
 /// This allows for the safe sharing and modification of state across threads.
 ///
+/// Cloning a `State` is cheap: all clones share the same lock and value.
+/// The lock can be used directly, but writes made through it bypass change notifications,
+/// versions and history, so prefer the setter and the provided traits for modifying it.
+///
+/// The constructors of `StateManager` return the state wrapped in a `ManagedState`, which
+/// dereferences to it and also holds the observers, history and version of the state.
+///
+/// The lock is a `std::sync::RwLock` by default. With the `parking-lot` feature enabled it is
+/// a `parking_lot::RwLock` instead, which is faster to acquire and is never poisoned. The API of
+/// the crate is the same for both backends; on the `parking-lot` backend lock operations simply
+/// never fail.
+///
+/// With the default `std` feature disabled, the crate depends only on `core` and `alloc` and the
+/// lock is a `spin::RwLock`. The state, its setter and the traits not tied to `std` (such as `Getter`,
+/// `Updater`, `Swapper`, `Setter`, `Access`, `History` and `Derive`) keep working, while
+/// subscriptions, waiting, registries and the features that require `std` are unavailable. Every
+/// such feature enables `std` itself, so features can be combined freely.
+///
+/// With the `tracing` feature enabled, lock acquisitions are wrapped in `trace` level spans and
+/// every write made by the setter emits a `trace` event, both tagged with the type of the state.
+///
 /// # Example
 ///
 /// ```
-/// let state: State<i32> = Arc::new(RwLock::new(Some(42)));
+/// use state_manager::{Getter, State, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, _) = Buffer::new_state(Some(42));
+/// let state: State<i32> = state.into_state();
+/// assert_eq!(state.get(), Some(42));
 /// ```
 pub type State<T> = Arc<RwLock<Option<T>>>;
 
-/// A type alias for a boxed dynamic closure that can modify the state.
+/// The operations the crate performs on a state besides plain reads.
+///
+/// It is implemented for `ManagedState`, whose writes go through its metadata, and for a bare
+/// `State`, which has none: its writes are neither published nor recorded, and it is never frozen.
+pub(crate) trait StateExt<T> {
+    /// Returns the lock holding the value.
+    fn state(&self) -> &State<T>;
+
+    /// Returns the metadata of this state, if it has any.
+    fn meta(&self) -> Option<&Meta<T>>;
+
+    /// Returns a `StateSetter` writing to this state.
+    fn setter(&self) -> StateSetter<T>
+        where Self: Clone + Send + Sync + 'static
+    {
+        let state_for_setter = self.clone();
+        Arc::new(move |data: Option<T>| -> error::Result<()> {
+            state_for_setter.set(data).map_err(|e| match state_for_setter.meta().and_then(Meta::name) {
+                // Prefixes the message of `e` with the name of the state
+                Some(name) => error::StateError::Default(alloc::format!("State '{}': {}", name, e)),
+                None => e,
+            })
+        })
+    }
+
+    /// Writes `data` to the state, recording the previous value if the state keeps a history.
+    ///
+    /// This is the write performed by the `StateSetter` returned from `StateManager`.
+    fn set(&self, data: Option<T>) -> error::Result<()> {
+        self.store(data).map_err(|(_, e)| e)
+    }
+
+    /// Writes `data` to the state like `set`, handing `data` back if it could not be written.
+    fn store(&self, data: Option<T>) -> Result<(), (Option<T>, error::StateError)> {
+        self.store_if(data, |_| true).map(|_| ())
+    }
+
+    /// Writes `data` to the state like `store`, provided that `condition` holds for the state
+    /// while it is locked for writing.
+    ///
+    /// Returns `Ok(false)` and drops `data` if the condition does not hold.
+    fn store_if<F>(&self, data: Option<T>, condition: F) -> Result<bool, (Option<T>, error::StateError)>
+        where F: FnOnce(&Self) -> bool
+    {
+        self.store_with(data, lock::Acquire::Blocking, condition)
+    }
+
+    /// Writes `data` to the state like `store_if`, acquiring the locks as `acquire` specifies.
+    fn store_with<F>(
+        &self,
+        data: Option<T>,
+        acquire: lock::Acquire,
+        condition: F,
+    ) -> Result<bool, (Option<T>, error::StateError)>
+        where F: FnOnce(&Self) -> bool
+    {
+        // Блокировка состояния берётся раньше истории, как и при публикации записи
+        let mut state_guard = match self.write_with(acquire) {
+            Ok(state_guard) => state_guard,
+            Err(e) => return Err((data, e)),
+        };
+        let history = self.meta().and_then(Meta::history);
+        let mut history = match history.map(|history| acquire.write(history)).transpose() {
+            Ok(history) => history,
+            Err(e) => return Err((data, e)),
+        };
+        if !condition(self) {
+            return Ok(false);
+        }
+        let previous = mem::replace(&mut *state_guard, data);
+        if let Some(history) = history.as_mut() {
+            history.record(previous);
+        }
+        drop(history);
+        self.publish(state_guard);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(state = core::any::type_name::<T>(), "state set");
+        Ok(true)
+    }
+
+    /// Acquires the write lock for a write made by the crate.
+    ///
+    /// Every write path goes through this method, so none of them can bypass freezing: it returns
+    /// `StateError::Frozen` if the state was frozen. The flag is checked under the lock, so no
+    /// write can complete after `freeze` returns.
+    fn write_for_setter(&self) -> error::Result<RwLockWriteGuard<'_, Option<T>>> {
+        self.write_with(lock::Acquire::Blocking)
+    }
+
+    /// Acquires the write lock like `write_for_setter`, as `acquire` specifies.
+    fn write_with(&self, acquire: lock::Acquire) -> error::Result<RwLockWriteGuard<'_, Option<T>>> {
+        let state_guard = acquire.write(self.state())?;
+        if self.is_frozen_locked() {
+            return Err(error::StateError::Frozen);
+        }
+        Ok(state_guard)
+    }
+
+    /// Returns whether the state is frozen.
+    ///
+    /// The flag only changes under the write lock, so the result is reliable for a write in
+    /// progress only when called while holding it.
+    fn is_frozen_locked(&self) -> bool {
+        self.meta().is_some_and(Meta::is_frozen)
+    }
+
+    /// Returns the number of writes made to the state, or `0` for a state without metadata.
+    ///
+    /// The version only changes under the write lock, so it matches the value while a lock on
+    /// the state is held.
+    fn current_version(&self) -> u64 {
+        self.meta().map_or(0, Meta::version)
+    }
+
+    /// Bumps the version, releases the write guard, wakes the waiting threads and notifies the
+    /// observers about the value it holds.
+    ///
+    /// Every write made by the crate goes through this method, or through `prepare_publish` and
+    /// `complete_publish` when several states are written at once. Only `undo` and `redo` publish
+    /// through their own path, which keeps the values available to redo. The observers are called
+    /// after the lock is released, so they are free to access the state themselves, and they
+    /// receive the values in the order of the writes. States built without notifications only
+    /// wake the waiting threads.
+    fn publish(&self, state_guard: RwLockWriteGuard<'_, Option<T>>) {
+        match self.meta() {
+            Some(meta) => meta.publish(state_guard),
+            None => drop(state_guard),
+        }
+    }
+
+    /// The first half of `publish`, called while the write lock is still held: clears the values
+    /// available to redo, bumps the version and queues the snapshot of the value for the observers.
+    fn prepare_publish(&self, state_guard: &RwLockWriteGuard<'_, Option<T>>) {
+        if let Some(meta) = self.meta() {
+            meta.prepare_publish(state_guard);
+        }
+    }
+
+    /// The second half of `publish`, called once the write lock is released: wakes the waiting
+    /// threads and delivers the queued snapshots to the observers.
+    fn complete_publish(&self) {
+        if let Some(meta) = self.meta() {
+            meta.complete_publish();
+        }
+    }
+}
+
+impl<T> StateExt<T> for State<T> {
+    fn state(&self) -> &State<T> {
+        self
+    }
+
+    fn meta(&self) -> Option<&Meta<T>> {
+        None
+    }
+}
+
+/// A type alias for a reference-counted dynamic closure that can modify the state.
 ///
 /// This closure takes an `Option<T>` as its input and returns a `Result<(), Error>`
 /// from the `error` module, allowing for error handling. It is both `Send` and `Sync`,
@@ -30,16 +227,21 @@ pub type State<T> = Arc<RwLock<Option<T>>>;
 /// - `Send`: Allows the `StateSetter` to be transferred across thread boundaries.
 /// - `Sync`: Allows the `StateSetter` to be accessed from multiple threads simultaneously.
 ///
+/// # Sharing
+/// The closure is stored in an `Arc`, so cloning a `StateSetter` is cheap and every clone
+/// writes to the same state. Clones can be handed to different threads without additional
+/// wrapping. Note that each clone keeps the state alive, just like a clone of the `State`.
+///
 /// # Examples
 ///
 /// ```rust
-///
-/// pub type StateSetter<T> = Box<dyn Fn(Option<T>) -> error::Result<()> + Send + Sync>;
+/// use std::sync::Arc;
+/// use state_manager::{error, StateSetter};
 ///
 /// // Example usage of `StateSetter`
 /// fn main() -> error::Result<()> {
 ///     // A sample state setter that simply prints the value or "Reset" if None
-///     let setter: StateSetter<String> = Box::new(|opt| {
+///     let setter: StateSetter<String> = Arc::new(|opt| {
 ///         match opt {
 ///             Some(value) => println!("New value: {}", value),
 ///             None => println!("State reset"),
@@ -54,38 +256,221 @@ pub type State<T> = Arc<RwLock<Option<T>>>;
 ///     Ok(())
 /// }
 /// ```
-pub type StateSetter<T> = Box<dyn Fn(Option<T>) -> error::Result<()> + Send + Sync>;
+pub type StateSetter<T> = Arc<dyn Fn(Option<T>) -> error::Result<()> + Send + Sync>;
+
+/// `FoldSetter` is the setter of a state created by `StateManager::new_fold_state`.
+///
+/// Instead of replacing the value, it takes an input of type `I` and folds it into the
+/// accumulated value. Like `StateSetter`, it is cheap to clone and safe to share between threads.
+pub type FoldSetter<I> = Arc<dyn Fn(I) -> error::Result<()> + Send + Sync>;
 
 
 /// Submodule defining possible errors.
 pub mod error;
 
+/// Submodule providing in-place mutation of the state.
+pub mod updater;
+
+/// Submodule providing atomic exchange of the stored value.
+pub mod swapper;
+
+/// Submodule providing additional ways of writing the state.
+pub mod setter;
+
+/// Submodule providing access to the value by reference.
+pub mod access;
+
+/// Submodule providing conditional modifications under a single lock.
+pub mod entry;
+
+/// Submodule providing cheap shared reads of values stored in an `Arc`.
+pub mod shared;
+
+/// Submodule providing in-place operations on states holding collections.
+pub mod collection;
+
+/// Submodule providing lock acquisition with a timeout.
+#[cfg(feature = "std")]
+pub mod timeout;
+
+/// Submodule abstracting over the lock backend.
+pub(crate) mod lock;
+
+/// Submodule providing undo of the changes made by the setter.
+pub mod history;
+
+/// Submodule providing states derived from other states.
+pub mod derived;
+
+/// Submodule providing interception of the writes made by the setter.
+pub mod middleware;
+
+/// Submodule providing the builder for configuring new states.
+pub mod builder;
+
+/// Submodule providing the state returned by `StateManager`, together with its metadata.
+pub mod managed;
+
+/// Submodule providing access counters for states.
+pub mod instrumented;
+
+/// Submodule providing containers of states keyed by type or by name.
+#[cfg(feature = "std")]
+pub mod registry;
+
+/// Submodule covering `serde` serialization of the state.
+///
+/// With the `serde` feature enabled, a `State<T>` serializes as the `Option<T>` it holds, through
+/// the implementations `serde` provides for `Arc` and for the lock. A `ManagedState<T>` serializes
+/// the same way, and a deserialized one starts with new metadata. The value is read under the
+/// read lock, so a consistent snapshot is serialized even if the state is being written
+/// concurrently, and serialization fails if the lock is poisoned. Deserializing creates a fresh
+/// state, unrelated to any existing one.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Getter, State, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, _) = Buffer::new_state(Some(42));
+/// assert_eq!(serde_json::to_string(&state).unwrap(), "42");
+///
+/// let state: State<i32> = serde_json::from_str("null").unwrap();
+/// assert_eq!(state.get(), None);
+/// ```
+#[cfg(feature = "serde")]
+pub mod serialization;
+
+/// Submodule providing reading and writing the state as JSON strings.
+#[cfg(feature = "json")]
+pub mod json;
+
+/// Submodule providing file-backed snapshots of the state.
+#[cfg(feature = "persist")]
+pub mod persist;
+
+/// Submodule managing the observers notified about state changes.
+pub(crate) mod notifier;
+
+/// Submodule defining the metadata kept for every managed state.
+pub(crate) mod meta;
+
+/// Submodule providing subscriptions to state changes.
+#[cfg(feature = "std")]
+pub mod observable;
+
+/// Submodule providing weak references to states.
+pub mod weak;
+
+/// Submodule providing ownership and reference-count introspection of states.
+pub mod ownership;
+
+/// Submodule providing reads and writes over several states at once.
+pub mod batch;
+
+/// Submodule providing read-only states.
+pub mod frozen;
+
+/// Submodule providing change detection through version numbers.
+pub mod version;
+
+/// Submodule providing blocking until the state satisfies a condition.
+#[cfg(feature = "std")]
+pub mod wait;
+
+/// Submodule providing the state backed by a `Mutex`.
+#[cfg(feature = "std")]
+pub mod mutex_state;
+
+/// Submodule providing the state with lock-free reads backed by `arc_swap::ArcSwapOption`.
+#[cfg(feature = "arc-swap")]
+pub mod arc_swap_state;
+
+/// Submodule providing the asynchronous state backed by `tokio::sync::RwLock`.
+#[cfg(feature = "tokio")]
+pub mod async_state;
+
+/// Submodule providing setters which limit the rate of the writes.
+#[cfg(feature = "tokio")]
+pub mod timing;
+
+pub use updater::Updater;
+pub use swapper::Swapper;
+pub use setter::Setter;
+pub use access::Access;
+pub use entry::StateEntry;
+pub use shared::ArcGetter;
+pub use collection::{Len, VecState};
+#[cfg(feature = "std")]
+pub use timeout::Timeout;
+#[cfg(feature = "std")]
+pub use observable::{Observable, SubscriptionHandle};
+#[cfg(feature = "std")]
+pub use wait::Wait;
+pub use weak::{downgrade, upgrade, WeakState};
+pub use ownership::{get_mut, into_inner, strong_count, weak_count};
+pub use batch::{get_all, set_all, snapshot_consistent, with_locked};
+pub use frozen::{freeze, Freeze, FrozenState};
+pub use version::{ChangeTracker, Versioned};
+pub use history::History;
+pub use derived::{combine, map_state, zip_get, Derive};
+pub use builder::StateBuilder;
+pub use managed::ManagedState;
+pub use middleware::Middleware;
+pub use instrumented::InstrumentedState;
+#[cfg(feature = "std")]
+pub use registry::{NamedStateStore, StateRegistry};
+#[cfg(feature = "json")]
+pub use json::Json;
+#[cfg(feature = "persist")]
+pub use persist::Persist;
+#[cfg(feature = "std")]
+pub use mutex_state::MutexState;
+#[cfg(feature = "arc-swap")]
+pub use arc_swap_state::ArcSwapState;
+#[cfg(feature = "tokio")]
+pub use async_state::{AsyncGetter, AsyncState, AsyncStateSetter};
+#[cfg(feature = "tokio")]
+pub use timing::{debounced_setter, throttled_setter};
+#[cfg(feature = "derive")]
+pub use state_manager_derive::StateBuffer;
+
 /// The `StateBuffer` trait defines the behavior of a state buffer.
 /// In this context, it acts as a marker trait without methods.
+///
+/// With the `derive` feature enabled, it can be implemented with `#[derive(StateBuffer)]`.
 pub trait StateBuffer{}
 
 /// The `StateManager` trait provides functionality for creating new states.
-/// It requires the state type `S` to be sendable across threads, synchronizable, have a default value,
-/// be cloneable, and have a `'static` lifetime.
+/// It requires the state type `S` to be sendable across threads, synchronizable, and have
+/// a `'static` lifetime.
+///
+/// The state type does not need to implement `Clone`: only reading a copy of the value through
+/// `Getter` requires it. Values of other types can be accessed by reference through `Access`.
 ///
 /// # Examples
 ///
+/// `StateManager` is implemented automatically for every `StateBuffer`:
+///
 /// ```
+/// use state_manager::{Getter, StateBuffer, StateManager};
+///
 /// struct MyStateBuffer;
 ///
 /// impl StateBuffer for MyStateBuffer {}
 ///
-/// impl StateManager<String> for MyStateBuffer {
-///     fn new_state(data: Option<String>) -> (State<String>, impl Fn(Option<String>) -> error::Result<()>) {
-///         // Implementation of the method
-///     }
-/// }
+/// let (state, set_state) = MyStateBuffer::new_state(Some(String::from("hello")));
+/// set_state(Some(String::from("world"))).unwrap();
+/// assert_eq!(state.get(), Some(String::from("world")));
 /// ```
 pub trait StateManager<S> 
     where 
-    S: Send + Sync + Default + Clone + 'static,
+    S: Send + Sync + 'static,
 {
-    /// Creates a new state with initial data and returns a tuple containing `State<S>` and a function for modifying it.
+    /// Creates a new state with initial data and returns a tuple containing `ManagedState<S>` and a function for modifying it.
     ///
     /// # Arguments
     ///
@@ -93,45 +478,289 @@ pub trait StateManager<S>
     ///
     /// # Return Value
     ///
-    /// Returns a tuple of `State<S>` and a function for modifying the state.
-    fn new_state(data: Option<S>) -> (State<S>, StateSetter<S>); 
+    /// Returns a tuple of `ManagedState<S>` and a function for modifying the state.
+    fn new_state(data: Option<S>) -> (ManagedState<S>, StateSetter<S>); 
+
+    /// Creates a new state which keeps a history of the values replaced by its setter.
+    ///
+    /// The history holds at most `capacity` entries; the oldest entries are dropped first.
+    /// Use the `History` trait to roll the changes back.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The initial state value of type `S`.
+    /// * `capacity` - The maximum number of previous values to keep.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a tuple of `ManagedState<S>` and a function for modifying the state.
+    fn new_history_state(data: Option<S>, capacity: usize) -> (ManagedState<S>, StateSetter<S>);
+
+    /// Creates a new state whose setter rejects the values not accepted by `validate`.
+    ///
+    /// A rejected write returns `StateError::Validation` and leaves the previous value intact.
+    /// The initial value is not validated, and writes made through the other traits of this
+    /// crate bypass the validator.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The initial state value of type `S`.
+    /// * `validate` - The function deciding whether a new value may be written.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a tuple of `ManagedState<S>` and a validating function for modifying the state.
+    fn new_validated_state<F>(data: Option<S>, validate: F) -> (ManagedState<S>, StateSetter<S>)
+        where F: Fn(&Option<S>) -> bool + Send + Sync + 'static;
+
+    /// Creates a new state whose setter runs every written value through `middlewares`.
+    ///
+    /// The middlewares run in order, each receiving the value produced by the previous one.
+    /// If any of them returns an error, the write is aborted and the state keeps its value.
+    /// The initial value does not go through the middlewares, and writes made through the
+    /// other traits of this crate bypass them.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The initial state value of type `S`.
+    /// * `middlewares` - The middlewares intercepting the writes made by the setter.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a tuple of `ManagedState<S>` and a function for modifying the state through the middlewares.
+    fn new_state_with_middleware(
+        data: Option<S>,
+        middlewares: Vec<Box<dyn Middleware<S>>>,
+    ) -> (ManagedState<S>, StateSetter<S>);
+
+    /// Creates a new state with a name identifying it in the errors reported by its setter.
+    ///
+    /// When many states exist, the name tells which one failed: an error returned by the setter
+    /// becomes a `StateError::Default` whose message starts with `State '<name>':`, followed by
+    /// the message of the original error.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the state.
+    /// * `data` - The initial state value of type `S`.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a tuple of `ManagedState<S>` and a function for modifying the state.
+    fn new_named_state(name: &str, data: Option<S>) -> (ManagedState<S>, StateSetter<S>);
+
+    /// Creates a new state whose setter reports lock failures with the error produced by `map`.
+    ///
+    /// This lets the setter fit into an application-specific error taxonomy. Only the failure
+    /// to acquire the lock, i.e. `StateError::Poisoned`, is replaced; other errors, such as
+    /// `StateError::Frozen`, are returned unchanged. On the `parking-lot` backend the lock never
+    /// fails, so `map` is never called.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The initial state value of type `S`.
+    /// * `map` - The function producing the error returned when the lock cannot be acquired.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a tuple of `ManagedState<S>` and a function for modifying the state.
+    fn new_state_with_error_map<F>(data: Option<S>, map: F) -> (ManagedState<S>, StateSetter<S>)
+        where F: Fn() -> error::StateError + Send + Sync + 'static;
+
+    /// Creates a new state whose initial value is produced by `init`.
+    ///
+    /// `init` is called exactly once, while the state is being constructed, so an expensive
+    /// value is only built when this constructor is actually reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The function producing the initial state value of type `S`.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a tuple of `ManagedState<S>` and a function for modifying the state.
+    fn new_state_from_fn<F: FnOnce() -> S>(init: F) -> (ManagedState<S>, StateSetter<S>);
+
+    /// Creates a new state accumulating the inputs passed to its setter, like a running total.
+    ///
+    /// The setter applies `f` to the accumulator and the input under a single write lock, so
+    /// concurrent inputs are never lost. If the state was cleared through the other traits of
+    /// this crate, the setter returns `StateError::Empty` and the input is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial` - The initial value of the accumulator.
+    /// * `f` - The function folding an input into the accumulator.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a tuple of `ManagedState<S>` and a function folding inputs into the state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use state_manager::{Getter, StateBuffer, StateManager};
+    ///
+    /// struct Buffer;
+    /// impl StateBuffer for Buffer {}
+    ///
+    /// let (total, add) = Buffer::new_fold_state(0, |total: &mut i32, input: i32| *total += input);
+    /// add(2).unwrap();
+    /// add(3).unwrap();
+    /// assert_eq!(total.get(), Some(5));
+    /// ```
+    fn new_fold_state<I, F>(initial: S, f: F) -> (ManagedState<S>, FoldSetter<I>)
+        where F: Fn(&mut S, I) + Send + Sync + 'static;
+
+    /// Creates a new asynchronous state with initial data and returns a tuple containing `AsyncState<S>`
+    /// and a function for modifying it.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The initial state value of type `S`.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a tuple of `AsyncState<S>` and a function returning a future that modifies the state.
+    #[cfg(feature = "tokio")]
+    fn new_async_state(data: Option<S>) -> (AsyncState<S>, AsyncStateSetter<S>);
+
+    /// Creates a new state backed by a `Mutex` instead of a read/write lock and returns a tuple
+    /// containing `MutexState<S>` and a function for modifying it.
+    ///
+    /// See `MutexState` for when to prefer it over `State`.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The initial state value of type `S`.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a tuple of `MutexState<S>` and a function for modifying the state.
+    #[cfg(feature = "std")]
+    fn new_mutex_state(data: Option<S>) -> (MutexState<S>, StateSetter<S>);
+
+    /// Creates a new state whose reads never lock and returns a tuple containing
+    /// `ArcSwapState<S>` and a function for modifying it.
+    ///
+    /// See `ArcSwapState` for when to prefer it over `State`.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The initial state value of type `S`.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a tuple of `ArcSwapState<S>` and a function for modifying the state.
+    #[cfg(feature = "arc-swap")]
+    fn new_arc_swap_state(data: Option<S>) -> (ArcSwapState<S>, StateSetter<S>);
 }
 
 /// Implement the `StateManager` trait for all types `T` that implement `StateBuffer`.
 impl<T, S> StateManager<S> for T
     where 
-    S: Send + Sync + Default + Clone + 'static,
+    S: Send + Sync + 'static,
     T: StateBuffer
 {
     
     fn new_state(data: Option<S>) -> (
-        State<S>, 
+        ManagedState<S>, 
         StateSetter<S>
     ) {
-        let (state, state_for_setter) = match data {
-            Some(value) => {
-                let state = Arc::new(RwLock::new(Some(value)));
-                let state_for_setter = state.clone();
-                (state, state_for_setter)
-            },
-            None => {
-                let state = Arc::new(RwLock::new(None));
-                let state_for_setter = state.clone(); 
-                (state, state_for_setter)   
-            },
-        };
+        let state = ManagedState::configured(data, None, true, None);
+        let setter = state.setter();
+        (state, setter)
+    }
+
+    fn new_history_state(data: Option<S>, capacity: usize) -> (ManagedState<S>, StateSetter<S>) {
+        let state = ManagedState::with_history(data, capacity);
+        let setter = state.setter();
+        (state, setter)
+    }
+
+    fn new_validated_state<F>(data: Option<S>, validate: F) -> (ManagedState<S>, StateSetter<S>)
+        where F: Fn(&Option<S>) -> bool + Send + Sync + 'static
+    {
+        let state = ManagedState::configured(data, None, true, None);
+        let state_for_setter = state.clone();
         let setter = move |data: Option<S>| -> error::Result<()> {
-            let state_guard_result = state_for_setter.write();
-            match state_guard_result {
-                Ok(mut state_guard) => {
-                    *state_guard = data;
-                    Ok(())
-                },
-                Err(_) => Err(error::StateError::Default(String::from("Lock error"))),
+            if !validate(&data) {
+                return Err(error::StateError::Validation(alloc::format!(
+                    "the value was rejected by the validator of a `{}` state",
+                    core::any::type_name::<S>()
+                )));
             }
+            state_for_setter.set(data)
+        };
+        (state, Arc::new(setter))
+    }
+
+    fn new_state_with_middleware(
+        data: Option<S>,
+        middlewares: Vec<Box<dyn Middleware<S>>>,
+    ) -> (ManagedState<S>, StateSetter<S>) {
+        let state = ManagedState::configured(data, None, true, None);
+        let state_for_setter = state.clone();
+        let setter = move |data: Option<S>| -> error::Result<()> {
+            middleware::set_through(&state_for_setter, &middlewares, data)
+        };
+        (state, Arc::new(setter))
+    }
+
+    fn new_named_state(name: &str, data: Option<S>) -> (ManagedState<S>, StateSetter<S>) {
+        let state = ManagedState::configured(data, None, true, Some(String::from(name)));
+        let setter = state.setter();
+        (state, setter)
+    }
+
+    fn new_state_with_error_map<F>(data: Option<S>, map: F) -> (ManagedState<S>, StateSetter<S>)
+        where F: Fn() -> error::StateError + Send + Sync + 'static
+    {
+        let state = ManagedState::configured(data, None, true, None);
+        let state_for_setter = state.clone();
+        let setter = move |data: Option<S>| -> error::Result<()> {
+            state_for_setter.set(data).map_err(|e| match e {
+                error::StateError::Poisoned => map(),
+                e => e,
+            })
+        };
+        (state, Arc::new(setter))
+    }
 
+    fn new_state_from_fn<F: FnOnce() -> S>(init: F) -> (ManagedState<S>, StateSetter<S>) {
+        Self::new_state(Some(init()))
+    }
+
+    fn new_fold_state<I, F>(initial: S, f: F) -> (ManagedState<S>, FoldSetter<I>)
+        where F: Fn(&mut S, I) + Send + Sync + 'static
+    {
+        let state = ManagedState::configured(Some(initial), None, true, None);
+        let state_for_setter = state.clone();
+        let setter = move |input: I| -> error::Result<()> {
+            let mut state_guard = state_for_setter.write_for_setter()?;
+            let Some(accumulator) = state_guard.as_mut() else {
+                return Err(error::StateError::Empty);
+            };
+            f(accumulator, input);
+            state_for_setter.publish(state_guard);
+            Ok(())
         };
-        (state, Box::new(setter))
+        (state, Arc::new(setter))
+    }
+
+    #[cfg(feature = "tokio")]
+    fn new_async_state(data: Option<S>) -> (AsyncState<S>, AsyncStateSetter<S>) {
+        async_state::new_async_state(data)
+    }
+
+    #[cfg(feature = "std")]
+    fn new_mutex_state(data: Option<S>) -> (MutexState<S>, StateSetter<S>) {
+        mutex_state::new_mutex_state(data)
+    }
+
+    #[cfg(feature = "arc-swap")]
+    fn new_arc_swap_state(data: Option<S>) -> (ArcSwapState<S>, StateSetter<S>) {
+        arc_swap_state::new_arc_swap_state(data)
     }
 }
 
@@ -140,7 +769,12 @@ impl<T, S> StateManager<S> for T
 /// # Examples
 ///
 /// ```
-/// let state: State<i32> = Arc::new(RwLock::new(Some(42)));
+/// use state_manager::{Getter, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, _) = Buffer::new_state(Some(42));
 /// assert_eq!(state.get(), Some(42));
 /// ```
 pub trait Getter<T> {
@@ -150,29 +784,167 @@ pub trait Getter<T> {
     ///
     /// Returns `Option<T>`, where `T` is the type of the value stored in the state.
     fn get(&self) -> Option<T>;
+
+    /// Returns the current value of the state without blocking.
+    ///
+    /// Unlike `get`, this method never waits for a writer to release the lock:
+    /// if the state is currently locked for writing, `None` is returned immediately.
+    /// This makes it suitable for polling state from threads that must not stall (e.g. UI threads).
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Option<T>`, which is `None` when the state is empty, locked for writing, or poisoned.
+    fn try_get(&self) -> Option<T>;
+
+    /// Returns the current value of the state, reporting lock failures.
+    ///
+    /// `get` returns `None` both for an empty state and for a poisoned lock. This method
+    /// distinguishes the two cases, so that lock poisoning is not silently ignored.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(Option<T>)` with the current value, or a `StateError` if the lock is poisoned.
+    fn try_get_result(&self) -> error::Result<Option<T>>;
+
+    /// Returns the current value of the state, or `default` if the state is empty.
+    ///
+    /// If the lock is poisoned, `default` is returned as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `default` - The value returned when the state holds no value.
+    fn get_or(&self, default: T) -> T;
+
+    /// Returns the current value of the state, or `T::default()` if the state is empty.
+    ///
+    /// If the lock is poisoned, `T::default()` is returned as well.
+    fn get_or_default(&self) -> T
+        where T: Default;
+
+    /// Returns the current value of the state, or the result of `f` if the state is empty.
+    ///
+    /// Like `Option::unwrap_or_else`, the closure is called only when there is no value,
+    /// which makes it suitable for fallbacks that are expensive to compute.
+    /// If the lock is poisoned, the result of `f` is returned as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure computing the fallback value.
+    fn get_or_else<F: FnOnce() -> T>(&self, f: F) -> T;
+
+    /// Returns the current value of the state if it satisfies `pred`.
+    ///
+    /// Like `Option::filter`, but the predicate is checked under the read lock, so the value
+    /// is cloned only when it is accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `pred` - The condition the value has to satisfy.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the value if it satisfies `pred`, or `None` if it does not, the state is empty,
+    /// or the lock is poisoned.
+    fn filter_get<F: Fn(&T) -> bool>(&self, pred: F) -> Option<T>;
+
+    /// Creates an independent copy of the state, with its own lock, holding the current value.
+    ///
+    /// Unlike `clone`, which shares the value, writes to the copy do not affect the original and
+    /// vice versa. The copy is a plain state: it has no history, and the observers of the
+    /// original are not carried over. An empty state yields an empty copy.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a tuple of the new `ManagedState<T>` and a function for modifying it, or a `StateError`
+    /// if the current value could not be read.
+    fn snapshot_state(&self) -> error::Result<(ManagedState<T>, StateSetter<T>)>
+        where T: Send + Sync + 'static
+    {
+        let state = ManagedState::configured(self.try_get_result()?, None, true, None);
+        let setter = state.setter();
+        Ok((state, setter))
+    }
+
+    /// Calls `f` with the current value of the state, borrowing it instead of cloning if possible.
+    ///
+    /// A `Cow` cannot outlive the lock guard, so it is passed to `f` rather than returned. `State`
+    /// lends `Cow::Borrowed` while holding the read lock, so `f` clones the value only if it calls
+    /// `into_owned`. Other implementors fall back to `get` and pass `Cow::Owned`. In the borrowed
+    /// case the lock is held while `f` runs, so `f` must not write to the same state.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure receiving the current value.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Some` with the result of `f`, or `None` if the state is empty or the lock is poisoned.
+    fn get_cow<R, F: FnOnce(Cow<'_, T>) -> R>(&self, f: F) -> Option<R>
+        where T: Clone
+    {
+        self.get().map(|value| f(Cow::Owned(value)))
+    }
 }
 
 impl<T> Getter<T> for State<T>
 where T: Clone
 {
     fn get(&self) -> Option<T> {
-        match self.read() {
-            Ok(state_guard) => {
-                let cl = state_guard.clone();
-                cl
-            },
+        match lock::read(self) {
+            Ok(state_guard) => state_guard.clone(),
             Err(_) => {
                 None::<T>
             },
         }
     }
+
+    fn try_get(&self) -> Option<T> {
+        match lock::try_read(self) {
+            Some(state_guard) => state_guard.clone(),
+            None => None::<T>,
+        }
+    }
+
+    fn try_get_result(&self) -> error::Result<Option<T>> {
+        let state_guard = lock::read(self)?;
+        Ok(state_guard.clone())
+    }
+
+    fn get_or(&self, default: T) -> T {
+        self.get().unwrap_or(default)
+    }
+
+    fn get_or_default(&self) -> T
+        where T: Default
+    {
+        self.get().unwrap_or_default()
+    }
+
+    fn get_or_else<F: FnOnce() -> T>(&self, f: F) -> T {
+        self.get().unwrap_or_else(f)
+    }
+
+    fn filter_get<F: Fn(&T) -> bool>(&self, pred: F) -> Option<T> {
+        match lock::read(self) {
+            Ok(state_guard) => state_guard.as_ref().filter(|value| pred(value)).cloned(),
+            Err(_) => None,
+        }
+    }
+
+    fn get_cow<R, F: FnOnce(Cow<'_, T>) -> R>(&self, f: F) -> Option<R> {
+        match lock::read(self) {
+            Ok(state_guard) => state_guard.as_ref().map(|value| f(Cow::Borrowed(value))),
+            Err(_) => None,
+        }
+    }
 } 
 
 #[cfg(test)]
 pub mod tests {
-    use std::{thread, time::Duration};
+    use std::{sync::mpsc, thread, time::{Duration, Instant}};
 
-    use crate::{Getter, StateBuffer, StateManager};
+    use crate::error::StateError;
+    use crate::{lock, Freeze, Getter, State, StateBuffer, StateManager};
 
     pub struct S;
     impl StateBuffer for S{}
@@ -239,8 +1011,7 @@ pub mod tests {
         for _ in 0..10 {
             let state_clone_for_reader = state_clone_for_readers.clone();
             let handle = std::thread::spawn(move || {
-                let read_value = state_clone_for_reader.get();
-                read_value
+                state_clone_for_reader.get()
             });
             reader_handles.push(handle);
         }
@@ -251,5 +1022,376 @@ pub mod tests {
             assert_eq!(read_value, Some(42));
         }
     }
-    
-}
\ No newline at end of file
+
+    #[test]
+    fn test_try_get_does_not_block() {
+        let (state, _setter) = S::new_state(Some(42));
+        let state_for_writer = state.clone();
+        let (locked_tx, locked_rx) = mpsc::channel();
+
+        // Поток-писатель удерживает блокировку на запись
+        let writer_handle = thread::spawn(move || {
+            let _guard = lock::write(&state_for_writer).unwrap();
+            locked_tx.send(()).unwrap();
+            thread::sleep(Duration::from_millis(500));
+        });
+        locked_rx.recv().unwrap();
+
+        let started = Instant::now();
+        assert_eq!(state.try_get(), None);
+        assert!(started.elapsed() < Duration::from_millis(100));
+
+        writer_handle.join().unwrap();
+        assert_eq!(state.try_get(), Some(42));
+    }
+
+    #[test]
+    fn test_try_get_empty_state() {
+        let (state, _setter) = S::new_state(None::<i32>);
+        assert_eq!(state.try_get(), None);
+    }
+
+    #[test]
+    fn test_try_get_result_ok() {
+        let (state, _setter) = S::new_state(Some(42));
+        assert_eq!(state.try_get_result().unwrap(), Some(42));
+        let (state, _setter) = S::new_state(None::<i32>);
+        assert_eq!(state.try_get_result().unwrap(), None);
+    }
+
+    #[cfg(all(feature = "std", not(feature = "parking-lot")))]
+    #[test]
+    fn test_try_get_result_poisoned() {
+        let (state, _setter) = S::new_state(Some(42));
+        let state_for_panic = state.clone();
+        // Паника при удержании блокировки на запись "отравляет" её
+        let _ = thread::spawn(move || {
+            let _guard = state_for_panic.write().unwrap();
+            panic!("poison the lock");
+        }).join();
+
+        assert!(matches!(state.try_get_result(), Err(StateError::Poisoned)));
+        assert_eq!(state.get(), None);
+    }
+
+    #[cfg(all(feature = "std", not(feature = "parking-lot")))]
+    #[test]
+    fn test_named_state_error_contains_name() {
+        let (state, set_state) = S::new_named_state("user_session", Some(42));
+        let state_for_panic = state.clone();
+        let _ = thread::spawn(move || {
+            let _guard = state_for_panic.write().unwrap();
+            panic!("poison the lock");
+        }).join();
+
+        let err = set_state(Some(1)).unwrap_err();
+        assert!(err.to_string().contains("'user_session'"));
+        // Исходное сообщение сохраняется
+        assert!(err.to_string().contains("poisoned"));
+    }
+
+    #[cfg(all(feature = "std", not(feature = "parking-lot")))]
+    #[test]
+    fn test_error_map_on_poison() {
+        let (state, set_state) = S::new_state_with_error_map(Some(42), || {
+            StateError::Default(String::from("session store unavailable"))
+        });
+        set_state(Some(1)).unwrap();
+
+        let state_for_panic = state.clone();
+        let _ = thread::spawn(move || {
+            let _guard = state_for_panic.write().unwrap();
+            panic!("poison the lock");
+        }).join();
+
+        assert_eq!(
+            set_state(Some(2)),
+            Err(StateError::Default(String::from("session store unavailable")))
+        );
+    }
+
+    #[test]
+    fn test_error_map_keeps_other_errors() {
+        let (state, set_state) = S::new_state_with_error_map(Some(1), || StateError::Empty);
+        state.freeze();
+        // Заморозка не является ошибкой блокировки
+        assert_eq!(set_state(Some(2)), Err(StateError::Frozen));
+    }
+
+    #[test]
+    fn test_snapshot_state_is_independent() {
+        let (state, set_state) = S::new_state(Some(vec![1]));
+        let (snapshot, set_snapshot) = state.snapshot_state().unwrap();
+        assert_eq!(snapshot.get(), Some(vec![1]));
+
+        set_snapshot(Some(vec![2])).unwrap();
+        assert_eq!(state.get(), Some(vec![1]));
+        set_state(None).unwrap();
+        assert_eq!(snapshot.get(), Some(vec![2]));
+
+        // Снимок пустого состояния тоже пуст
+        let (empty, _) = state.snapshot_state().unwrap();
+        assert_eq!(empty.get(), None);
+    }
+
+    #[test]
+    fn test_state_built_from_lock() {
+        use alloc::sync::Arc;
+        use crate::{ManagedState, Updater, Versioned};
+
+        // Состояние, созданное напрямую, работает и без метаданных
+        let state: State<i32> = Arc::new(lock::RwLock::new(Some(1)));
+        state.update(|value| *value = value.map(|v| v + 1)).unwrap();
+        assert_eq!(state.get(), Some(2));
+
+        // Обёртка добавляет метаданные, начиная с нулевой версии
+        let state = ManagedState::new(state);
+        assert_eq!(state.version(), 0);
+        state.update(|value| *value = value.map(|v| v + 1)).unwrap();
+        assert_eq!(state.get(), Some(3));
+        assert_eq!(state.version(), 1);
+    }
+
+    #[test]
+    fn test_get_cow_avoids_clones() {
+        use alloc::borrow::Cow;
+        use alloc::sync::Arc;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Counted {
+            clones: Arc<AtomicUsize>,
+            len: usize,
+        }
+        impl Clone for Counted {
+            fn clone(&self) -> Self {
+                self.clones.fetch_add(1, Ordering::SeqCst);
+                Counted { clones: self.clones.clone(), len: self.len }
+            }
+        }
+
+        let clones = Arc::new(AtomicUsize::new(0));
+        let (state, _) = S::new_state(Some(Counted { clones: clones.clone(), len: 3 }));
+
+        assert_eq!(state.get().map(|value| value.len), Some(3));
+        assert_eq!(clones.load(Ordering::SeqCst), 1);
+
+        // заимствование без клонирования
+        assert_eq!(state.get_cow(|value| matches!(value, Cow::Borrowed(_))), Some(true));
+        assert_eq!(state.get_cow(|value| value.len), Some(3));
+        assert_eq!(clones.load(Ordering::SeqCst), 1);
+
+        assert_eq!(state.get_cow(|value| value.into_owned().len), Some(3));
+        assert_eq!(clones.load(Ordering::SeqCst), 2);
+
+        let (empty, _) = S::new_state(None::<Counted>);
+        assert_eq!(empty.get_cow(|value| value.len), None);
+    }
+
+    #[test]
+    fn test_named_state_reads_and_writes() {
+        let (state, set_state) = S::new_named_state("counter", Some(0));
+        set_state(Some(1)).unwrap();
+        assert_eq!(state.get(), Some(1));
+    }
+
+    #[cfg(feature = "parking-lot")]
+    #[test]
+    fn test_panic_while_writing_does_not_poison() {
+        let (state, set_state) = S::new_state(Some(42));
+        let state_for_panic = state.clone();
+        // Блокировки parking_lot не "отравляются" при панике
+        let _ = thread::spawn(move || {
+            let _guard = lock::write(&state_for_panic).unwrap();
+            panic!("panic while holding the lock");
+        }).join();
+
+        assert_eq!(state.try_get_result().unwrap(), Some(42));
+        set_state(Some(1)).unwrap();
+        assert_eq!(state.get(), Some(1));
+    }
+
+    #[test]
+    fn test_get_or() {
+        let (state, set_state) = S::new_state(Some(42));
+        assert_eq!(state.get_or(0), 42);
+        set_state(None).unwrap();
+        assert_eq!(state.get_or(0), 0);
+    }
+
+    #[test]
+    fn test_get_or_default() {
+        let (state, set_state) = S::new_state(Some(String::from("value")));
+        assert_eq!(state.get_or_default(), String::from("value"));
+        set_state(None).unwrap();
+        assert_eq!(state.get_or_default(), String::new());
+    }
+
+    #[cfg(all(feature = "std", not(feature = "parking-lot")))]
+    #[test]
+    fn test_get_or_poisoned() {
+        let (state, _setter) = S::new_state(Some(42));
+        let state_for_panic = state.clone();
+        let _ = thread::spawn(move || {
+            let _guard = state_for_panic.write().unwrap();
+            panic!("poison the lock");
+        }).join();
+
+        // При "отравленной" блокировке возвращается значение по умолчанию
+        assert_eq!(state.get_or(1), 1);
+        assert_eq!(state.get_or_default(), 0);
+    }
+
+    #[test]
+    fn test_get_or_else_is_lazy() {
+        let (state, _setter) = S::new_state(Some(42));
+        // Замыкание не должно вызываться, пока в состоянии есть значение
+        assert_eq!(state.get_or_else(|| panic!("fallback must not be computed")), 42);
+    }
+
+    #[test]
+    fn test_get_or_else_empty_state() {
+        let (state, _setter) = S::new_state(None);
+        assert_eq!(state.get_or_else(|| 6 * 7), 42);
+    }
+
+    #[test]
+    fn test_state_without_default() {
+        // Тип намеренно не реализует Default
+        #[derive(Clone, Debug, PartialEq)]
+        struct Port(u16);
+
+        let (state, set_state) = S::new_state(Some(Port(80)));
+        set_state(Some(Port(443))).unwrap();
+        assert_eq!(state.get(), Some(Port(443)));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tracing_test::traced_test]
+    #[test]
+    fn test_set_emits_trace_event() {
+        let (_, set_state) = S::new_state(Some(1));
+        set_state(Some(2)).unwrap();
+        assert!(logs_contain("state set"));
+        assert!(logs_contain("state=\"i32\""));
+    }
+
+    #[test]
+    fn test_setter_cloned_into_threads() {
+        let (state, set_state) = S::new_state(Some(Vec::new()));
+        let mut handles = vec![];
+
+        for id in 0..2 {
+            let set_state = set_state.clone();
+            let state = state.clone();
+            handles.push(thread::spawn(move || {
+                // Оба клона сеттера пишут в одно и то же состояние
+                let mut values = state.get().unwrap();
+                values.push(id);
+                set_state(Some(values)).unwrap();
+            }));
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let values = state.get().unwrap();
+        assert!(!values.is_empty() && values.len() <= 2);
+        set_state(Some(vec![42])).unwrap();
+        assert_eq!(state.get(), Some(vec![42]));
+    }
+
+    #[cfg(not(feature = "std"))]
+    #[test]
+    fn test_no_std_get_and_set() {
+        // Только core и alloc: состояние на spin::RwLock
+        use alloc::string::String;
+
+        let (state, set_state) = S::new_state(Some(String::from("embedded")));
+        assert_eq!(state.get(), Some(String::from("embedded")));
+        set_state(None).unwrap();
+        assert_eq!(state.try_get_result().unwrap(), None);
+    }
+
+    #[test]
+    fn test_filter_get_accepts() {
+        let (state, _) = S::new_state(Some(42));
+        assert_eq!(state.filter_get(|value| value % 2 == 0), Some(42));
+    }
+
+    #[test]
+    fn test_filter_get_rejects() {
+        let (state, _) = S::new_state(Some(41));
+        assert_eq!(state.filter_get(|value| value % 2 == 0), None);
+        // Пустое состояние не вызывает предикат
+        let (state, _) = S::new_state(None::<i32>);
+        assert_eq!(state.filter_get(|_| unreachable!()), None);
+    }
+
+    #[test]
+    fn test_validated_setter_accepts() {
+        let (state, set_state) = S::new_validated_state(Some(1), |value| value.is_none_or(|v| v > 0));
+        set_state(Some(2)).unwrap();
+        assert_eq!(state.get(), Some(2));
+        set_state(None).unwrap();
+        assert_eq!(state.get(), None);
+    }
+
+    #[test]
+    fn test_validated_setter_rejects() {
+        let (state, set_state) = S::new_validated_state(Some(1), |value| value.is_none_or(|v| v > 0));
+        let err = set_state(Some(-1)).unwrap_err();
+        assert!(matches!(err, StateError::Validation(_)));
+        assert_eq!(
+            err.to_string(),
+            "Validation failed: the value was rejected by the validator of a `i32` state"
+        );
+        // Отклонённое значение не изменяет состояние
+        assert_eq!(state.get(), Some(1));
+    }
+
+    #[test]
+    fn test_new_state_from_fn_calls_init_once() {
+        let calls = std::cell::Cell::new(0);
+        let (state, set_state) = S::new_state_from_fn(|| {
+            calls.set(calls.get() + 1);
+            String::from("expensive")
+        });
+        assert_eq!(calls.get(), 1);
+        assert_eq!(state.get(), Some(String::from("expensive")));
+
+        // Последующие записи и чтения не вызывают init повторно
+        set_state(None).unwrap();
+        state.get();
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_fold_state_running_total() {
+        let (total, add) = S::new_fold_state(0, |total: &mut i32, input: i32| *total += input);
+        for input in [1, 2, 3, 4] {
+            add(input).unwrap();
+        }
+        assert_eq!(total.get(), Some(10));
+    }
+
+    #[test]
+    fn test_fold_state_across_threads() {
+        let (total, add) = S::new_fold_state(0u64, |total: &mut u64, input: u64| *total += input);
+        let mut handles = vec![];
+
+        for _ in 0..8 {
+            let add = add.clone();
+            handles.push(thread::spawn(move || {
+                for _ in 0..100 {
+                    add(1).unwrap();
+                }
+            }));
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // Ни один вход не потерян
+        assert_eq!(total.get(), Some(800));
+    }
+}
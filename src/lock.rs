@@ -0,0 +1,259 @@
+#[cfg(all(feature = "std", not(feature = "parking-lot")))]
+pub(crate) use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+#[cfg(all(feature = "std", not(feature = "parking-lot")))]
+use std::sync::TryLockError;
+
+#[cfg(feature = "parking-lot")]
+pub(crate) use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[cfg(not(feature = "std"))]
+pub(crate) use spin::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[cfg(feature = "std")]
+use std::time::Duration;
+#[cfg(feature = "parking-lot")]
+use std::time::Instant;
+
+use crate::error;
+
+/// Enters a `tracing` span named `$name` until the end of the enclosing function, tagged with
+/// the type of the value protected by the lock. Expands to nothing without the `tracing` feature.
+macro_rules! lock_span {
+    ($name:literal, $T:ty) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!($name, state = core::any::type_name::<$T>()).entered();
+    };
+}
+
+/// Acquires the read lock, blocking until it is available.
+///
+/// Returns `StateError::Poisoned` if the lock is poisoned.
+#[cfg(all(feature = "std", not(feature = "parking-lot")))]
+pub(crate) fn read<T>(lock: &RwLock<T>) -> error::Result<RwLockReadGuard<'_, T>> {
+    lock_span!("read_lock", T);
+    Ok(lock.read()?)
+}
+
+/// Acquires the read lock, blocking until it is available.
+///
+/// `parking_lot` and `spin` locks are never poisoned, so this never fails.
+#[cfg(any(feature = "parking-lot", not(feature = "std")))]
+pub(crate) fn read<T>(lock: &RwLock<T>) -> error::Result<RwLockReadGuard<'_, T>> {
+    lock_span!("read_lock", T);
+    Ok(lock.read())
+}
+
+/// Acquires the write lock, blocking until it is available.
+///
+/// Returns `StateError::Poisoned` if the lock is poisoned.
+#[cfg(all(feature = "std", not(feature = "parking-lot")))]
+pub(crate) fn write<T>(lock: &RwLock<T>) -> error::Result<RwLockWriteGuard<'_, T>> {
+    lock_span!("write_lock", T);
+    Ok(lock.write()?)
+}
+
+/// Acquires the write lock, blocking until it is available.
+///
+/// `parking_lot` and `spin` locks are never poisoned, so this never fails.
+#[cfg(any(feature = "parking-lot", not(feature = "std")))]
+pub(crate) fn write<T>(lock: &RwLock<T>) -> error::Result<RwLockWriteGuard<'_, T>> {
+    lock_span!("write_lock", T);
+    Ok(lock.write())
+}
+
+/// Attempts to acquire the read lock without blocking.
+///
+/// Returns `None` if the lock is held for writing or poisoned.
+#[cfg(all(feature = "std", not(feature = "parking-lot")))]
+pub(crate) fn try_read<T>(lock: &RwLock<T>) -> Option<RwLockReadGuard<'_, T>> {
+    lock_span!("try_read_lock", T);
+    lock.try_read().ok()
+}
+
+/// Attempts to acquire the read lock without blocking.
+///
+/// Returns `None` if the lock is held for writing.
+#[cfg(any(feature = "parking-lot", not(feature = "std")))]
+pub(crate) fn try_read<T>(lock: &RwLock<T>) -> Option<RwLockReadGuard<'_, T>> {
+    lock_span!("try_read_lock", T);
+    lock.try_read()
+}
+
+/// Attempts to acquire the write lock without blocking.
+///
+/// Returns `Ok(None)` if the lock is currently held, or `StateError::Poisoned` if it is poisoned.
+#[cfg(all(feature = "std", not(feature = "parking-lot")))]
+pub(crate) fn try_write<T>(lock: &RwLock<T>) -> error::Result<Option<RwLockWriteGuard<'_, T>>> {
+    lock_span!("try_write_lock", T);
+    match lock.try_write() {
+        Ok(guard) => Ok(Some(guard)),
+        Err(TryLockError::WouldBlock) => Ok(None),
+        Err(TryLockError::Poisoned(_)) => Err(error::StateError::Poisoned),
+    }
+}
+
+/// Attempts to acquire the write lock without blocking.
+///
+/// Returns `Ok(None)` if the lock is currently held. `parking_lot` and `spin` locks are never
+/// poisoned, so this never fails.
+#[cfg(any(feature = "parking-lot", not(feature = "std")))]
+pub(crate) fn try_write<T>(lock: &RwLock<T>) -> error::Result<Option<RwLockWriteGuard<'_, T>>> {
+    lock_span!("try_write_lock", T);
+    Ok(lock.try_write())
+}
+
+/// Consumes the lock, returning the value it protects.
+///
+/// Returns `StateError::Poisoned` if the lock is poisoned.
+#[cfg(all(feature = "std", not(feature = "parking-lot")))]
+pub(crate) fn into_inner<T>(lock: RwLock<T>) -> error::Result<T> {
+    Ok(lock.into_inner()?)
+}
+
+/// Consumes the lock, returning the value it protects.
+///
+/// `parking_lot` and `spin` locks are never poisoned, so this never fails.
+#[cfg(any(feature = "parking-lot", not(feature = "std")))]
+pub(crate) fn into_inner<T>(lock: RwLock<T>) -> error::Result<T> {
+    Ok(lock.into_inner())
+}
+
+/// Returns a mutable reference to the value protected by the lock, without locking it.
+///
+/// Returns `None` if the lock is poisoned.
+#[cfg(all(feature = "std", not(feature = "parking-lot")))]
+pub(crate) fn get_mut<T>(lock: &mut RwLock<T>) -> Option<&mut T> {
+    lock.get_mut().ok()
+}
+
+/// Returns a mutable reference to the value protected by the lock, without locking it.
+///
+/// `parking_lot` and `spin` locks are never poisoned, so this always succeeds.
+#[cfg(any(feature = "parking-lot", not(feature = "std")))]
+pub(crate) fn get_mut<T>(lock: &mut RwLock<T>) -> Option<&mut T> {
+    Some(lock.get_mut())
+}
+
+/// Acquires the read lock, ignoring poisoning.
+#[cfg(all(feature = "std", not(feature = "parking-lot")))]
+pub(crate) fn read_recovering<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock_span!("read_lock", T);
+    lock.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Acquires the read lock, ignoring poisoning.
+#[cfg(any(feature = "parking-lot", not(feature = "std")))]
+pub(crate) fn read_recovering<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock_span!("read_lock", T);
+    lock.read()
+}
+
+/// Acquires the write lock, clearing the poison flag if it is set.
+#[cfg(all(feature = "std", not(feature = "parking-lot")))]
+pub(crate) fn write_recovering<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock_span!("write_lock", T);
+    let guard = lock.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    lock.clear_poison();
+    guard
+}
+
+/// Acquires the write lock, clearing the poison flag if it is set.
+///
+/// `parking_lot` and `spin` locks are never poisoned, so this is an ordinary write.
+#[cfg(any(feature = "parking-lot", not(feature = "std")))]
+pub(crate) fn write_recovering<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock_span!("write_lock", T);
+    lock.write()
+}
+
+/// Acquires the read lock, waiting at most `timeout`.
+///
+/// Returns `StateError::Timeout` holding `timeout` if the lock could not be acquired in time.
+#[cfg(feature = "parking-lot")]
+pub(crate) fn read_for<T>(lock: &RwLock<T>, timeout: Duration) -> error::Result<RwLockReadGuard<'_, T>> {
+    lock_span!("read_lock", T);
+    lock.try_read_for(timeout).ok_or(error::StateError::Timeout(timeout))
+}
+
+/// Acquires the read lock, spinning on `try_read` for at most `timeout`.
+///
+/// Returns `StateError::Timeout` holding `timeout` if the lock could not be acquired in time,
+/// or `StateError::Poisoned` if the lock is poisoned.
+#[cfg(all(feature = "std", not(feature = "parking-lot")))]
+pub(crate) fn read_spinning<T>(lock: &RwLock<T>, timeout: Duration) -> error::Result<RwLockReadGuard<'_, T>> {
+    lock_span!("read_lock", T);
+    let deadline = std::time::Instant::now() + timeout;
+    loop {
+        match lock.try_read() {
+            Ok(guard) => return Ok(guard),
+            Err(TryLockError::Poisoned(_)) => return Err(error::StateError::Poisoned),
+            Err(TryLockError::WouldBlock) if std::time::Instant::now() >= deadline => {
+                return Err(error::StateError::Timeout(timeout));
+            }
+            Err(TryLockError::WouldBlock) => std::thread::yield_now(),
+        }
+    }
+}
+
+/// Acquires the read lock, waiting at most `timeout`.
+///
+/// `parking_lot` supports timed acquisition natively, so this simply defers to `read_for`.
+#[cfg(feature = "parking-lot")]
+pub(crate) fn read_spinning<T>(lock: &RwLock<T>, timeout: Duration) -> error::Result<RwLockReadGuard<'_, T>> {
+    read_for(lock, timeout)
+}
+
+/// Acquires the write lock, waiting until `deadline` at most.
+///
+/// Returns `StateError::Timeout` holding `timeout`, the wait the deadline was computed from, if
+/// the lock could not be acquired in time.
+#[cfg(feature = "parking-lot")]
+pub(crate) fn write_until<T>(
+    lock: &RwLock<T>,
+    deadline: Instant,
+    timeout: Duration,
+) -> error::Result<RwLockWriteGuard<'_, T>> {
+    lock_span!("write_lock", T);
+    lock.try_write_until(deadline).ok_or(error::StateError::Timeout(timeout))
+}
+
+/// Returns the address of `lock`, which is the same for all clones of the state holding it.
+///
+/// Used to lock several states in a consistent order.
+pub(crate) fn address<T>(lock: &RwLock<T>) -> usize {
+    lock as *const RwLock<T> as *const () as usize
+}
+
+/// How a write acquires its locks.
+///
+/// A write may need several locks, e.g. the history and the value of a state, so a timed write
+/// shares one deadline between them.
+#[derive(Clone, Copy)]
+pub(crate) enum Acquire {
+    /// Waits for each lock as long as it takes.
+    Blocking,
+    /// Waits for the locks until `deadline`, failing with `StateError::Timeout` holding `timeout`.
+    #[cfg(feature = "parking-lot")]
+    Until { deadline: Instant, timeout: Duration },
+}
+
+impl Acquire {
+    /// Waits for the locks at most `timeout` in total, or as long as it takes if the deadline
+    /// cannot be represented.
+    #[cfg(feature = "parking-lot")]
+    pub(crate) fn within(timeout: Duration) -> Self {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => Acquire::Until { deadline, timeout },
+            None => Acquire::Blocking,
+        }
+    }
+
+    /// Acquires the write lock of `lock`.
+    pub(crate) fn write<T>(self, lock: &RwLock<T>) -> error::Result<RwLockWriteGuard<'_, T>> {
+        match self {
+            Acquire::Blocking => write(lock),
+            #[cfg(feature = "parking-lot")]
+            Acquire::Until { deadline, timeout } => write_until(lock, deadline, timeout),
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use alloc::string::String;
+use alloc::sync::{Arc, Weak};
+use core::borrow::Borrow;
+use core::ops::Deref;
+
+use crate::lock::RwLock;
+use crate::meta::Meta;
+use crate::{State, StateExt, StateSetter};
+
+/// A state together with the metadata the crate keeps for it: its observers, history, version
+/// and frozen flag.
+///
+/// `StateManager::new_state` and the other constructors of `RwLock` based states return a
+/// `ManagedState` whose setter shares the same metadata, so writes made through either of them
+/// notify the observers and record the history.
+/// A `ManagedState` dereferences to the wrapped `State`, so it can be passed wherever a `&State`
+/// is expected. Writes made through a bare `State`, e.g. one returned by `into_state`, bypass the
+/// metadata.
+///
+/// It can also be created empty through `Default`, so structs holding states can simply
+/// `#[derive(Default)]`. Cloning it shares the value and the metadata, like cloning a `State`.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Getter, ManagedState};
+///
+/// #[derive(Default)]
+/// struct Session {
+///     user_id: ManagedState<u64>,
+/// }
+///
+/// let session = Session::default();
+/// assert_eq!(session.user_id.get(), None);
+///
+/// let set_user_id = session.user_id.setter();
+/// set_user_id(Some(7)).unwrap();
+/// assert_eq!(session.user_id.get(), Some(7));
+/// ```
+pub struct ManagedState<T> {
+    state: State<T>,
+    meta: Arc<Meta<T>>,
+}
+
+impl<T> ManagedState<T> {
+    /// Wraps `state` with new metadata: no observers, no history, and notifications enabled.
+    ///
+    /// The metadata belongs to the returned value and its clones only. Wrapping the same `State`
+    /// twice yields two managed states which do not see each other's observers or versions.
+    pub fn new(state: State<T>) -> Self {
+        ManagedState::configured_from(state, None, true, None)
+    }
+
+    /// Creates a new state holding `data`, keeping up to `history` previous values if it is set,
+    /// notifying observers about writes if `notifications` is `true`, and reporting the errors of
+    /// its setter under `name` if it is set.
+    pub(crate) fn configured(data: Option<T>, history: Option<usize>, notifications: bool, name: Option<String>) -> Self {
+        ManagedState::configured_from(Arc::new(RwLock::new(data)), history, notifications, name)
+    }
+
+    /// Creates a new state holding `data` which records up to `capacity` previous values.
+    pub(crate) fn with_history(data: Option<T>, capacity: usize) -> Self {
+        ManagedState::configured(data, Some(capacity), true, None)
+    }
+
+    fn configured_from(state: State<T>, history: Option<usize>, notifications: bool, name: Option<String>) -> Self {
+        ManagedState {
+            state,
+            meta: Arc::new(Meta::new(history, notifications, name)),
+        }
+    }
+
+    /// Returns a `StateSetter` writing to the state, like the one returned by
+    /// `StateManager::new_state`.
+    pub fn setter(&self) -> StateSetter<T>
+        where T: Send + Sync + 'static
+    {
+        StateExt::setter(self)
+    }
+
+    /// Returns the wrapped state.
+    pub fn into_state(self) -> State<T> {
+        self.state
+    }
+
+    /// Returns the metadata of the state.
+    pub(crate) fn meta(&self) -> &Meta<T> {
+        &self.meta
+    }
+
+    /// Returns a reference to the state which does not keep it alive.
+    pub(crate) fn downgrade(&self) -> WeakManaged<T> {
+        WeakManaged {
+            state: Arc::downgrade(&self.state),
+            meta: Arc::downgrade(&self.meta),
+        }
+    }
+}
+
+impl<T> Default for ManagedState<T> {
+    fn default() -> Self {
+        ManagedState::configured(None, None, true, None)
+    }
+}
+
+impl<T> Clone for ManagedState<T> {
+    fn clone(&self) -> Self {
+        ManagedState {
+            state: self.state.clone(),
+            meta: self.meta.clone(),
+        }
+    }
+}
+
+impl<T> Deref for ManagedState<T> {
+    type Target = State<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.state
+    }
+}
+
+impl<T> Borrow<State<T>> for ManagedState<T> {
+    fn borrow(&self) -> &State<T> {
+        &self.state
+    }
+}
+
+impl<T> StateExt<T> for ManagedState<T> {
+    fn state(&self) -> &State<T> {
+        &self.state
+    }
+
+    fn meta(&self) -> Option<&Meta<T>> {
+        Some(&self.meta)
+    }
+}
+
+/// Creates a state holding `Some(value)`.
+///
+/// The state is created without a setter; take one from `ManagedState::setter` when needed.
+/// `StateManager::new_state`, which also returns the setter, remains the primary way of creating
+/// states.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Getter, ManagedState};
+///
+/// let state: ManagedState<i32> = 42.into();
+/// assert_eq!(state.get(), Some(42));
+/// ```
+impl<T> From<T> for ManagedState<T> {
+    fn from(value: T) -> Self {
+        ManagedState::configured(Some(value), None, true, None)
+    }
+}
+
+/// Creates a state holding `data`, which may be empty.
+///
+/// Like `From<T>`, this creates the state without a setter.
+impl<T> From<Option<T>> for ManagedState<T> {
+    fn from(data: Option<T>) -> Self {
+        ManagedState::configured(data, None, true, None)
+    }
+}
+
+/// A reference to a `ManagedState` which does not keep it alive, held by the observers of the
+/// states it is derived from.
+pub(crate) struct WeakManaged<T> {
+    state: Weak<RwLock<Option<T>>>,
+    meta: Weak<Meta<T>>,
+}
+
+impl<T> WeakManaged<T> {
+    /// Returns the state, or `None` if every clone of it and its setters were dropped.
+    pub(crate) fn upgrade(&self) -> Option<ManagedState<T>> {
+        Some(ManagedState {
+            state: self.state.upgrade()?,
+            meta: self.meta.upgrade()?,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::{Getter, ManagedState, Updater};
+
+    #[derive(Default)]
+    struct Counters {
+        hits: ManagedState<i32>,
+        misses: ManagedState<i32>,
+    }
+
+    #[test]
+    fn test_derive_default_with_managed_state() {
+        let counters = Counters::default();
+        assert_eq!(counters.hits.get(), None);
+        assert_eq!(counters.misses.get(), None);
+
+        counters.hits.setter()(Some(1)).unwrap();
+        counters.hits.update(|value| *value = value.map(|hits| hits + 1)).unwrap();
+        assert_eq!(counters.hits.get(), Some(2));
+        assert_eq!(counters.misses.get(), None);
+    }
+
+    #[test]
+    fn test_managed_state_clone_shares_value() {
+        let state = ManagedState::default();
+        let state_clone = state.clone();
+        state.setter()(Some(String::from("shared"))).unwrap();
+        assert_eq!(state_clone.get(), Some(String::from("shared")));
+        assert_eq!(state_clone.into_state().get(), Some(String::from("shared")));
+    }
+
+    #[test]
+    fn test_from_value() {
+        let state: ManagedState<i32> = 42.into();
+        assert_eq!(state.get(), Some(42));
+        // Сеттер, полученный из состояния, пишет в него же
+        state.setter()(Some(43)).unwrap();
+        assert_eq!(state.get(), Some(43));
+        let state = ManagedState::from(String::from("state"));
+        assert_eq!(state.get(), Some(String::from("state")));
+    }
+
+    #[test]
+    fn test_from_option() {
+        let state: ManagedState<i32> = None.into();
+        assert_eq!(state.get(), None);
+        let state: ManagedState<i32> = Some(1).into();
+        assert_eq!(state.get(), Some(1));
+    }
+}
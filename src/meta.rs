@@ -0,0 +1,145 @@
+use alloc::string::String;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use crate::history::HistoryLog;
+use crate::lock::{self, RwLock, RwLockWriteGuard};
+use crate::notifier::Notifier;
+#[cfg(feature = "std")]
+use crate::wait::Waiters;
+
+/// The data the crate keeps for a state next to its value: the observers, the history, the
+/// version and the flags set when the state was configured.
+///
+/// It is owned by the `ManagedState` returned from `StateManager`, and shared by its clones and
+/// its setter, so it lives exactly as long as the state can still be written through the crate.
+pub(crate) struct Meta<T> {
+    notifier: Notifier<T>,
+    history: Option<RwLock<HistoryLog<T>>>,
+    /// Whether writes are delivered to the observers registered in `notifier`.
+    notifications: bool,
+    /// The name used in the errors reported by the setter.
+    name: Option<String>,
+    /// Whether the state rejects writes. Only changed under the write lock of the state.
+    frozen: AtomicBool,
+    /// The number of writes made to the state. Only changed under the write lock of the state.
+    version: AtomicU64,
+    #[cfg(feature = "std")]
+    waiters: Waiters,
+    /// The sender publishing to the receivers returned by `Observable::watch`, created by the
+    /// first of them.
+    #[cfg(feature = "tokio")]
+    watch: std::sync::OnceLock<tokio::sync::watch::Sender<Option<T>>>,
+}
+
+impl<T> Meta<T> {
+    /// Creates the metadata of a new state, keeping up to `history` previous values if it is set,
+    /// notifying observers about writes if `notifications` is `true`, and reporting the errors of
+    /// its setter under `name` if it is set.
+    pub(crate) fn new(history: Option<usize>, notifications: bool, name: Option<String>) -> Self {
+        Meta {
+            notifier: Notifier::new(),
+            history: history.map(|capacity| RwLock::new(HistoryLog::new(capacity))),
+            notifications,
+            name,
+            frozen: AtomicBool::new(false),
+            version: AtomicU64::new(0),
+            #[cfg(feature = "std")]
+            waiters: Waiters::new(),
+            #[cfg(feature = "tokio")]
+            watch: std::sync::OnceLock::new(),
+        }
+    }
+
+    /// Returns the name of the state, if it has one.
+    pub(crate) fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the history of the state, if it keeps one.
+    pub(crate) fn history(&self) -> Option<&RwLock<HistoryLog<T>>> {
+        self.history.as_ref()
+    }
+
+    /// Returns the notifier holding the observers of the state.
+    pub(crate) fn notifier(&self) -> &Notifier<T> {
+        &self.notifier
+    }
+
+    /// Returns the threads waiting for a change of the state.
+    #[cfg(feature = "std")]
+    pub(crate) fn waiters(&self) -> &Waiters {
+        &self.waiters
+    }
+
+    /// Returns the sender of the `watch` channel of the state, creating it with `init` if no
+    /// receiver was requested yet.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn watch_sender<F>(&self, init: F) -> &tokio::sync::watch::Sender<Option<T>>
+        where F: FnOnce() -> tokio::sync::watch::Sender<Option<T>>
+    {
+        self.watch.get_or_init(init)
+    }
+
+    /// Returns whether the state is frozen.
+    pub(crate) fn is_frozen(&self) -> bool {
+        self.frozen.load(Ordering::Acquire)
+    }
+
+    /// Marks the state as frozen. Must be called under the write lock of the state.
+    pub(crate) fn freeze(&self) {
+        self.frozen.store(true, Ordering::Release);
+    }
+
+    /// Returns the number of writes made to the state.
+    pub(crate) fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+
+    /// Bumps the version, releases the write guard, wakes the waiting threads and notifies the
+    /// observers about the value it holds.
+    ///
+    /// A new write invalidates the values that were undone, so the redo stack of the history is
+    /// cleared as well.
+    pub(crate) fn publish(&self, state_guard: RwLockWriteGuard<'_, Option<T>>) {
+        self.prepare_publish(&state_guard);
+        drop(state_guard);
+        self.complete_publish();
+    }
+
+    /// Publishes a value restored by `undo` or `redo` like `publish`, keeping the redo stack.
+    ///
+    /// Must be called after the lock of the history is released.
+    pub(crate) fn publish_restored(&self, state_guard: RwLockWriteGuard<'_, Option<T>>) {
+        self.announce(&state_guard);
+        drop(state_guard);
+        self.complete_publish();
+    }
+
+    /// The first half of `publish`, called while the write lock is still held: clears the redo
+    /// stack, bumps the version and queues the snapshot of the value for the observers.
+    ///
+    /// Must be called after the lock of the history is released: the history is always locked
+    /// after the state.
+    pub(crate) fn prepare_publish(&self, state_guard: &RwLockWriteGuard<'_, Option<T>>) {
+        if let Some(history) = &self.history {
+            lock::write_recovering(history).clear_redo();
+        }
+        self.announce(state_guard);
+    }
+
+    /// Bumps the version and queues the snapshot of the value for the observers.
+    fn announce(&self, state_guard: &RwLockWriteGuard<'_, Option<T>>) {
+        self.version.fetch_add(1, Ordering::Release);
+        if self.notifications {
+            self.notifier.enqueue(state_guard);
+        }
+    }
+
+    /// The second half of `publish`, called once the write lock is released: wakes the waiting
+    /// threads and delivers the queued snapshots to the observers.
+    pub(crate) fn complete_publish(&self) {
+        #[cfg(feature = "std")]
+        self.waiters.wake();
+        self.notifier.deliver();
+    }
+}
@@ -0,0 +1,114 @@
+use alloc::boxed::Box;
+
+use crate::{error, ManagedState, StateExt};
+
+/// The `Middleware` trait intercepts the writes made through the setter of a state.
+///
+/// Middlewares are passed to `StateManager::new_state_with_middleware` and run in order on every
+/// write. Each of them receives the value produced by the previous one and may pass it on, replace
+/// it, or veto the write by returning an error. This allows layering behaviors such as logging,
+/// validation and normalization.
+///
+/// Middlewares run while the write lock is held, so they must not access the same state.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{error, Getter, Middleware, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// struct Trim;
+///
+/// impl Middleware<String> for Trim {
+///     fn before(&self, next: &Option<String>, _prev: &Option<String>) -> error::Result<Option<String>> {
+///         Ok(next.as_ref().map(|s| s.trim().to_string()))
+///     }
+/// }
+///
+/// let (state, set_state) = Buffer::new_state_with_middleware(None, vec![Box::new(Trim)]);
+/// set_state(Some(String::from("  hello  "))).unwrap();
+/// assert_eq!(state.get(), Some(String::from("hello")));
+/// ```
+pub trait Middleware<T>: Send + Sync {
+    /// Called before a new value is stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `next` - The value about to be stored, as produced by the previous middleware.
+    /// * `prev` - The value currently held by the state.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the value to pass to the next middleware (or to store, if this is the last one),
+    /// or a `StateError` aborting the write. An aborted write leaves the state unchanged.
+    fn before(&self, next: &Option<T>, prev: &Option<T>) -> error::Result<Option<T>>;
+}
+
+/// Runs `data` through `middlewares` and stores the result in `state`.
+pub(crate) fn set_through<T>(
+    state: &ManagedState<T>,
+    middlewares: &[Box<dyn Middleware<T>>],
+    data: Option<T>,
+) -> error::Result<()> {
+    let mut state_guard = state.write_for_setter()?;
+    let mut value = data;
+    for middleware in middlewares {
+        value = middleware.before(&value, &state_guard)?;
+    }
+    *state_guard = value;
+    state.publish(state_guard);
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::error::{self, StateError};
+    use crate::tests::S;
+    use crate::{Getter, Middleware, StateManager};
+
+    struct Uppercase;
+
+    impl Middleware<String> for Uppercase {
+        fn before(&self, next: &Option<String>, _prev: &Option<String>) -> error::Result<Option<String>> {
+            Ok(next.as_ref().map(|s| s.to_uppercase()))
+        }
+    }
+
+    /// Запрещает очистку состояния и повтор текущего значения
+    struct RejectNoneAndRepeats;
+
+    impl Middleware<String> for RejectNoneAndRepeats {
+        fn before(&self, next: &Option<String>, prev: &Option<String>) -> error::Result<Option<String>> {
+            match next {
+                None => Err(StateError::Validation("the state cannot be cleared".to_string())),
+                Some(_) if next == prev => Err(StateError::Validation("the value did not change".to_string())),
+                Some(_) => Ok(next.clone()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_middlewares_compose_in_order() {
+        let (state, set_state) = S::new_state_with_middleware(
+            Some(String::from("A")),
+            vec![Box::new(Uppercase), Box::new(RejectNoneAndRepeats)],
+        );
+
+        set_state(Some(String::from("b"))).unwrap();
+        assert_eq!(state.get(), Some(String::from("B")));
+
+        // Второй мидлвар видит уже преобразованное значение и отклоняет повтор
+        assert!(matches!(set_state(Some(String::from("b"))), Err(StateError::Validation(_))));
+        assert!(matches!(set_state(None), Err(StateError::Validation(_))));
+        assert_eq!(state.get(), Some(String::from("B")));
+    }
+
+    #[test]
+    fn test_no_middlewares() {
+        let (state, set_state) = S::new_state_with_middleware(Some(1), vec![]);
+        set_state(Some(2)).unwrap();
+        assert_eq!(state.get(), Some(2));
+    }
+}
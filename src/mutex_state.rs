@@ -0,0 +1,171 @@
+use std::sync::{Arc, Mutex, TryLockError};
+
+use crate::{error, Getter, StateSetter};
+
+/// Defines the `MutexState` type as an atomically reference-counted mutex containing an optional
+/// value of type `T`.
+///
+/// `State` is backed by a read/write lock, which lets readers proceed in parallel but makes every
+/// acquisition more expensive. When writes are about as frequent as reads, the parallel reads
+/// rarely pay off and a `MutexState` is cheaper. Prefer `State` for read-heavy workloads and
+/// whenever observers, history or the other traits of this crate are needed: a `MutexState`
+/// only supports `Getter` and its setter.
+///
+/// # Example
+///
+/// ```
+/// use state_manager::{Getter, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (counter, set_counter) = Buffer::new_mutex_state(Some(0));
+/// set_counter(Some(1)).unwrap();
+/// assert_eq!(counter.get(), Some(1));
+/// ```
+pub type MutexState<T> = Arc<Mutex<Option<T>>>;
+
+impl<T> Getter<T> for MutexState<T>
+where T: Clone
+{
+    fn get(&self) -> Option<T> {
+        match self.lock() {
+            Ok(state_guard) => state_guard.clone(),
+            Err(_) => None,
+        }
+    }
+
+    fn try_get(&self) -> Option<T> {
+        match self.try_lock() {
+            Ok(state_guard) => state_guard.clone(),
+            Err(TryLockError::WouldBlock) | Err(TryLockError::Poisoned(_)) => None,
+        }
+    }
+
+    fn try_get_result(&self) -> error::Result<Option<T>> {
+        let state_guard = self.lock()?;
+        Ok(state_guard.clone())
+    }
+
+    fn get_or(&self, default: T) -> T {
+        self.get().unwrap_or(default)
+    }
+
+    fn get_or_default(&self) -> T
+        where T: Default
+    {
+        self.get().unwrap_or_default()
+    }
+
+    fn get_or_else<F: FnOnce() -> T>(&self, f: F) -> T {
+        self.get().unwrap_or_else(f)
+    }
+
+    fn filter_get<F: Fn(&T) -> bool>(&self, pred: F) -> Option<T> {
+        match self.lock() {
+            Ok(state_guard) => state_guard.as_ref().filter(|value| pred(value)).cloned(),
+            Err(_) => None,
+        }
+    }
+}
+
+/// Creates a new `MutexState` and its setter. Used by `StateManager::new_mutex_state`.
+pub(crate) fn new_mutex_state<S>(data: Option<S>) -> (MutexState<S>, StateSetter<S>)
+    where
+    S: Send + 'static,
+{
+    let state = Arc::new(Mutex::new(data));
+    let state_for_setter = state.clone();
+    let setter = move |data: Option<S>| -> error::Result<()> {
+        let mut state_guard = state_for_setter.lock()?;
+        *state_guard = data;
+        Ok(())
+    };
+    (state, Arc::new(setter))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::mpsc;
+    use std::thread;
+
+    use crate::error::StateError;
+    use crate::tests::S;
+    use crate::{Getter, StateManager};
+
+    #[test]
+    fn test_mutex_init_some_value() {
+        let (state, _) = S::new_mutex_state(Some(42));
+        assert_eq!(state.get(), Some(42));
+    }
+
+    #[test]
+    fn test_mutex_init_none_value() {
+        let (state, _) = S::new_mutex_state(None::<i32>);
+        assert_eq!(state.get(), None);
+    }
+
+    #[test]
+    fn test_mutex_set_new_value() {
+        let (state, set_state) = S::new_mutex_state(Some(42));
+        set_state(None).unwrap();
+        assert_eq!(state.get(), None);
+        set_state(Some(1)).unwrap();
+        assert_eq!(state.try_get_result().unwrap(), Some(1));
+        assert_eq!(state.filter_get(|value| *value > 0), Some(1));
+    }
+
+    #[test]
+    fn test_mutex_multiple_threads_writing() {
+        let (state, set_state) = S::new_mutex_state(Some(0));
+        let mut handles = vec![];
+
+        for id in 1..=10 {
+            let set_state = set_state.clone();
+            handles.push(thread::spawn(move || set_state(Some(id)).unwrap()));
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let value = state.get().unwrap();
+        assert!((1..=10).contains(&value));
+    }
+
+    #[test]
+    fn test_mutex_try_get_does_not_block() {
+        let (state, _) = S::new_mutex_state(Some(42));
+        let state_for_holder = state.clone();
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        let holder_handle = thread::spawn(move || {
+            let _guard = state_for_holder.lock().unwrap();
+            locked_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        // Мьютекс занят => try_get сразу возвращает None
+        assert_eq!(state.try_get(), None);
+
+        release_tx.send(()).unwrap();
+        holder_handle.join().unwrap();
+        assert_eq!(state.try_get(), Some(42));
+    }
+
+    #[test]
+    fn test_mutex_poisoned() {
+        let (state, set_state) = S::new_mutex_state(Some(42));
+        let state_for_panic = state.clone();
+        let _ = thread::spawn(move || {
+            let _guard = state_for_panic.lock().unwrap();
+            panic!("poison the lock");
+        }).join();
+
+        assert_eq!(state.get(), None);
+        assert_eq!(state.get_or(0), 0);
+        assert!(matches!(state.try_get_result(), Err(StateError::Poisoned)));
+        assert!(matches!(set_state(Some(1)), Err(StateError::Poisoned)));
+    }
+}
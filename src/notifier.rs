@@ -0,0 +1,128 @@
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::lock::{self, RwLock};
+
+/// A callback invoked with the new value after every write to the state.
+///
+/// The callback returns `false` once it is no longer interested in changes
+/// (e.g. its receiver was dropped), after which it is removed from the notifier.
+pub(crate) type Observer<T> = Arc<dyn Fn(&Option<T>) -> bool + Send + Sync>;
+
+/// Produces a copy of the stored value.
+type Cloner<T> = fn(&Option<T>) -> Option<T>;
+
+/// The values waiting to be delivered to the observers.
+struct Queue<T> {
+    /// The snapshots of the written values, in the order of the writes.
+    values: VecDeque<Option<T>>,
+    /// Whether a thread is currently delivering the queued values.
+    delivering: bool,
+}
+
+/// Keeps the observers of a state and delivers new values to them.
+///
+/// The values are delivered in the order in which they were written: a snapshot is queued while
+/// the write lock of the state is held, and the queue is drained by a single thread at a time.
+pub(crate) struct Notifier<T> {
+    observers: RwLock<Vec<Observer<T>>>,
+    /// Clones the stored value so that observers can be called without holding the state lock.
+    /// It is set by the first registered observer, since registration requires `T: Clone`.
+    cloner: RwLock<Option<Cloner<T>>>,
+    queue: RwLock<Queue<T>>,
+}
+
+/// Lets another thread deliver the queued values if an observer panics.
+struct Delivering<'a, T> {
+    queue: &'a RwLock<Queue<T>>,
+}
+
+impl<T> Drop for Delivering<'_, T> {
+    fn drop(&mut self) {
+        lock::write_recovering(self.queue).delivering = false;
+    }
+}
+
+impl<T> Notifier<T> {
+    pub(crate) fn new() -> Self {
+        Notifier {
+            observers: RwLock::new(Vec::new()),
+            cloner: RwLock::new(None),
+            queue: RwLock::new(Queue {
+                values: VecDeque::new(),
+                delivering: false,
+            }),
+        }
+    }
+
+    /// Registers a new observer.
+    pub(crate) fn register(&self, observer: Observer<T>)
+        where T: Clone
+    {
+        lock::write_recovering(&self.cloner).get_or_insert(Option::<T>::clone);
+        lock::write_recovering(&self.observers).push(observer);
+    }
+
+    /// Queues a copy of `value` for the observers, unless there is nobody to notify.
+    ///
+    /// Must be called while holding the write lock of the state, so that the values are queued
+    /// in the order of the writes.
+    pub(crate) fn enqueue(&self, value: &Option<T>) {
+        let Some(cloner) = *lock::read_recovering(&self.cloner) else {
+            return;
+        };
+        if lock::read_recovering(&self.observers).is_empty() {
+            return;
+        }
+        lock::write_recovering(&self.queue).values.push_back(cloner(value));
+    }
+
+    /// Delivers the queued values to the observers, in order.
+    ///
+    /// Must be called after the write lock of the state is released. If another thread is
+    /// already delivering, it delivers the values queued by this one as well, and this call
+    /// returns immediately. The same applies to a write made by an observer.
+    pub(crate) fn deliver(&self) {
+        {
+            let mut queue = lock::write_recovering(&self.queue);
+            if queue.delivering || queue.values.is_empty() {
+                return;
+            }
+            queue.delivering = true;
+        }
+        let delivering = Delivering { queue: &self.queue };
+        loop {
+            let value = {
+                let mut queue = lock::write_recovering(&self.queue);
+                match queue.values.pop_front() {
+                    Some(value) => value,
+                    None => {
+                        // Очередь пуста: флаг снимается под той же блокировкой, что и проверка
+                        queue.delivering = false;
+                        break;
+                    },
+                }
+            };
+            self.notify(&value);
+        }
+        // Флаг уже снят: другой поток мог снова его установить
+        mem::forget(delivering);
+    }
+
+    /// Calls every observer with `value`, removing the ones that are no longer interested.
+    fn notify(&self, value: &Option<T>) {
+        // Observers are called on a copy of the list, so they may register new observers themselves
+        let observers = lock::read_recovering(&self.observers).clone();
+        let finished: Vec<Observer<T>> = observers
+            .into_iter()
+            .filter(|observer| !observer(value))
+            .collect();
+
+        if !finished.is_empty() {
+            lock::write_recovering(&self.observers)
+                .retain(|observer| !finished.iter().any(|f| Arc::ptr_eq(f, observer)));
+        }
+    }
+}
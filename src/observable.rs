@@ -0,0 +1,475 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+
+use crate::{error, ManagedState};
+
+/// A handle keeping a callback registered by `Observable::on_change` subscribed.
+///
+/// Dropping the handle, or calling `unsubscribe`, stops the callback from being called by
+/// subsequent writes; the callback itself is released on the next write, like a dropped
+/// `subscribe` receiver. Keep the handle for as long as the callback is needed.
+#[must_use = "dropping the handle unsubscribes the callback"]
+pub struct SubscriptionHandle {
+    active: Arc<AtomicBool>,
+}
+
+impl SubscriptionHandle {
+    /// Creates an active handle and the flag the callback checks before every call.
+    fn new() -> (Self, Arc<AtomicBool>) {
+        let active = Arc::new(AtomicBool::new(true));
+        (SubscriptionHandle { active: active.clone() }, active)
+    }
+
+    /// Unsubscribes the callback. Equivalent to dropping the handle.
+    pub fn unsubscribe(self) {}
+}
+
+impl Drop for SubscriptionHandle {
+    fn drop(&mut self) {
+        self.active.store(false, Ordering::Release);
+    }
+}
+
+/// The `Observable` trait provides subscriptions to the changes of the state.
+///
+/// Every write made through the setter returned by `StateManager::new_state`
+/// (or through the other traits of this crate) is published to all subscribers.
+///
+/// The subscribers receive the values in the order of the writes, even when several threads
+/// write concurrently, so the last value received is always the current value of the state.
+/// To keep that order, the values are delivered by one thread at a time: a write may return
+/// before its value is delivered, in which case the thread delivering the previous values
+/// delivers it as well.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Observable, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, set_state) = Buffer::new_state(Some(1));
+/// let receiver = state.subscribe();
+/// set_state(Some(2)).unwrap();
+/// assert_eq!(receiver.recv().unwrap(), Some(2));
+/// ```
+pub trait Observable<T> {
+    /// Returns a `std::sync::mpsc::Receiver` which receives every new value of the state.
+    ///
+    /// Unlike `watch`, this does not require an async runtime. Values written before the
+    /// subscription are not delivered. Dropping the receiver is safe: it is removed from
+    /// the subscribers on the next write.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a `mpsc::Receiver<Option<T>>` receiving a value per write.
+    fn subscribe(&self) -> mpsc::Receiver<Option<T>>;
+
+    /// Registers a callback which is called with the new value after every write.
+    ///
+    /// Callbacks are called after the write lock is released, so no state lock is held
+    /// while they run: a callback may read or even write the state without deadlocking.
+    /// Callbacks registered on the same state are called in the order of registration.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The callback receiving a reference to the new value.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the `SubscriptionHandle` of the callback. The callback is called until the handle
+    /// is dropped.
+    fn on_change<F: Fn(&Option<T>) + Send + Sync + 'static>(&self, f: F) -> SubscriptionHandle;
+
+    /// Registers a callback which is called with the new value after the next write only.
+    ///
+    /// The callback is removed once it has run, so no unsubscription is needed. Like `on_change`,
+    /// it runs after the write lock is released.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The callback receiving a reference to the new value.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` once the callback is registered.
+    fn observe_once<F: FnOnce(&Option<T>) + Send + 'static>(&self, f: F) -> error::Result<()>;
+
+    /// Registers a callback which is called with the previous and the new value after every write.
+    ///
+    /// The callback keeps its own copy of the last value it has seen, starting with the value
+    /// held at registration, so a state that started empty reports `None` as the previous value
+    /// of its first write. Like `on_change`, it runs after the write lock is released.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The callback receiving `(previous, current)`.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the `SubscriptionHandle` of the callback once it is registered, or a `StateError`
+    /// if the lock could not be acquired to read the current value. The callback is called until
+    /// the handle is dropped.
+    fn on_change_diff<F>(&self, f: F) -> error::Result<SubscriptionHandle>
+        where F: Fn(Option<&T>, Option<&T>) + Send + Sync + 'static;
+
+    /// Returns a `tokio::sync::watch::Receiver` that always holds the latest value of the state.
+    ///
+    /// The receiver is initialized with the current value. Dropping the receiver is safe:
+    /// the setter keeps working and simply stops publishing to it.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a `watch::Receiver<Option<T>>` which is marked as changed after every write.
+    #[cfg(feature = "tokio")]
+    fn watch(&self) -> tokio::sync::watch::Receiver<Option<T>>
+        where T: Sync;
+
+    /// Returns a `futures::Stream` yielding every new value of the state.
+    ///
+    /// Like `subscribe`, values written before the call are not yielded. The stream ends once
+    /// every clone of the state and its setter are dropped, after yielding the values written
+    /// before. Dropping the stream is safe: it is removed from the subscribers on the next write.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a `Stream<Item = Option<T>>` yielding a value per write.
+    #[cfg(feature = "futures")]
+    fn changes(&self) -> impl futures::Stream<Item = Option<T>> + Send + 'static;
+}
+
+impl<T> Observable<T> for ManagedState<T>
+where T: Clone + Send + Sync + 'static
+{
+    fn subscribe(&self) -> mpsc::Receiver<Option<T>> {
+        let (sender, receiver) = mpsc::channel();
+        self.meta().notifier().register(Arc::new(move |value: &Option<T>| {
+            // Отправка завершается ошибкой только если получатель удалён => удаляем подписчика
+            sender.send(value.clone()).is_ok()
+        }));
+        receiver
+    }
+
+    fn on_change<F: Fn(&Option<T>) + Send + Sync + 'static>(&self, f: F) -> SubscriptionHandle {
+        let (handle, active) = SubscriptionHandle::new();
+        self.meta().notifier().register(Arc::new(move |value: &Option<T>| {
+            if !active.load(Ordering::Acquire) {
+                return false;
+            }
+            f(value);
+            true
+        }));
+        handle
+    }
+
+    fn observe_once<F: FnOnce(&Option<T>) + Send + 'static>(&self, f: F) -> error::Result<()> {
+        let f = Mutex::new(Some(f));
+        self.meta().notifier().register(Arc::new(move |value: &Option<T>| {
+            // При одновременных записях замыкание забирает только первая из них
+            if let Some(f) = f.lock().unwrap_or_else(|e| e.into_inner()).take() {
+                f(value);
+            }
+            false
+        }));
+        Ok(())
+    }
+
+    fn on_change_diff<F>(&self, f: F) -> error::Result<SubscriptionHandle>
+        where F: Fn(Option<&T>, Option<&T>) + Send + Sync + 'static
+    {
+        // Как и в watch, регистрируем под блокировкой на чтение, чтобы не пропустить запись
+        let state_guard = crate::lock::read(self)?;
+        let previous = Mutex::new(state_guard.clone());
+        let (handle, active) = SubscriptionHandle::new();
+        self.meta().notifier().register(Arc::new(move |value: &Option<T>| {
+            if !active.load(Ordering::Acquire) {
+                return false;
+            }
+            let mut previous = previous.lock().unwrap_or_else(|e| e.into_inner());
+            f(previous.as_ref(), value.as_ref());
+            *previous = value.clone();
+            true
+        }));
+        Ok(handle)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn watch(&self) -> tokio::sync::watch::Receiver<Option<T>>
+        where T: Sync
+    {
+        // Канал создаётся под блокировкой на чтение, чтобы не пропустить запись между
+        // чтением начального значения и регистрацией наблюдателя
+        let state_guard = crate::lock::read_recovering(self);
+        let sender = self.meta().watch_sender(|| {
+            let sender = tokio::sync::watch::Sender::new(state_guard.clone());
+            let sender_for_observer = sender.clone();
+            self.meta().notifier().register(Arc::new(move |value: &Option<T>| {
+                sender_for_observer.send_replace(value.clone());
+                true
+            }));
+            sender
+        });
+        sender.subscribe()
+    }
+
+    #[cfg(feature = "futures")]
+    fn changes(&self) -> impl futures::Stream<Item = Option<T>> + Send + 'static {
+        let (sender, receiver) = futures::channel::mpsc::unbounded();
+        self.meta().notifier().register(Arc::new(move |value: &Option<T>| {
+            sender.unbounded_send(value.clone()).is_ok()
+        }));
+        receiver
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::mpsc::TryRecvError;
+    use std::sync::{Arc, Mutex};
+
+    use crate::tests::S;
+    use crate::{Getter, Observable, StateManager};
+
+    #[test]
+    fn test_subscribe_two_subscribers() {
+        let (state, set_state) = S::new_state(Some(1));
+        let first = state.subscribe();
+        let second = state.subscribe();
+
+        set_state(Some(2)).unwrap();
+        assert_eq!(first.recv().unwrap(), Some(2));
+        assert_eq!(second.recv().unwrap(), Some(2));
+    }
+
+    #[test]
+    fn test_subscribe_does_not_receive_previous_values() {
+        let (state, set_state) = S::new_state(Some(1));
+        set_state(Some(2)).unwrap();
+        let receiver = state.subscribe();
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    }
+
+    #[test]
+    fn test_subscribe_dropped_receiver_is_pruned() {
+        let (state, set_state) = S::new_state(Some(1));
+        let dropped = state.subscribe();
+        let kept = state.subscribe();
+        drop(dropped);
+
+        set_state(Some(2)).unwrap();
+        set_state(None).unwrap();
+        assert_eq!(kept.recv().unwrap(), Some(2));
+        assert_eq!(kept.recv().unwrap(), None);
+    }
+
+    #[test]
+    fn test_on_change_observes_new_value() {
+        let (state, set_state) = S::new_state(Some(1));
+        let observed = Arc::new(Mutex::new(vec![]));
+
+        let observed_clone = observed.clone();
+        let state_clone = state.clone();
+        let _subscription = state.on_change(move |value| {
+            // Блокировка состояния не удерживается => чтение внутри колбэка не приводит к взаимоблокировке
+            assert_eq!(state_clone.get(), *value);
+            observed_clone.lock().unwrap().push(*value);
+        });
+
+        set_state(Some(2)).unwrap();
+        set_state(None).unwrap();
+        assert_eq!(*observed.lock().unwrap(), vec![Some(2), None]);
+    }
+
+    #[test]
+    fn test_on_change_concurrent_writes_arrive_in_order() {
+        use std::thread;
+
+        let (state, set_state) = S::new_state(Some(0));
+        let observed = Arc::new(Mutex::new(vec![]));
+        let observed_clone = observed.clone();
+        let _subscription = state.on_change(move |value| {
+            observed_clone.lock().unwrap().push(value.unwrap());
+        });
+
+        let writers: Vec<_> = (0..8).map(|thread_index| {
+            let set_state = set_state.clone();
+            thread::spawn(move || {
+                for i in 1..=200 {
+                    set_state(Some(thread_index * 1_000 + i)).unwrap();
+                }
+            })
+        }).collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        // Последнее доставленное значение совпадает с текущим, записи каждого потока идут по порядку
+        let observed = observed.lock().unwrap();
+        assert_eq!(observed.len(), 8 * 200);
+        assert_eq!(observed.last().copied(), state.get());
+        for thread_index in 0..8 {
+            let values: Vec<_> = observed.iter().filter(|v| *v / 1_000 == thread_index).collect();
+            assert!(values.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+
+    #[test]
+    fn test_on_change_callback_writing_the_state() {
+        let (state, set_state) = S::new_state(Some(0));
+        let observed = Arc::new(Mutex::new(vec![]));
+        let observed_clone = observed.clone();
+        let set_from_callback = set_state.clone();
+        let _subscription = state.on_change(move |value| {
+            observed_clone.lock().unwrap().push(*value);
+            // Запись из колбэка доставляется после текущего значения
+            if *value == Some(1) {
+                set_from_callback(Some(2)).unwrap();
+            }
+        });
+
+        set_state(Some(1)).unwrap();
+        assert_eq!(*observed.lock().unwrap(), vec![Some(1), Some(2)]);
+        assert_eq!(state.get(), Some(2));
+    }
+
+    #[test]
+    fn test_on_change_multiple_callbacks() {
+        let (state, set_state) = S::new_state(None);
+        let calls = Arc::new(Mutex::new(vec![]));
+        let mut subscriptions = vec![];
+
+        for id in 0..3 {
+            let calls_clone = calls.clone();
+            subscriptions.push(state.on_change(move |value: &Option<i32>| {
+                calls_clone.lock().unwrap().push((id, *value));
+            }));
+        }
+
+        set_state(Some(42)).unwrap();
+        assert_eq!(*calls.lock().unwrap(), vec![(0, Some(42)), (1, Some(42)), (2, Some(42))]);
+    }
+
+    #[test]
+    fn test_dropped_handle_unsubscribes() {
+        let (state, set_state) = S::new_state(Some(0));
+        let calls = Arc::new(Mutex::new(0));
+
+        let calls_clone = calls.clone();
+        let subscription = state.on_change(move |_| *calls_clone.lock().unwrap() += 1);
+        set_state(Some(1)).unwrap();
+        drop(subscription);
+        set_state(Some(2)).unwrap();
+        assert_eq!(*calls.lock().unwrap(), 1);
+
+        // Явная отписка работает так же
+        let calls_clone = calls.clone();
+        let subscription = state.on_change(move |_| *calls_clone.lock().unwrap() += 1);
+        subscription.unsubscribe();
+        set_state(Some(3)).unwrap();
+        assert_eq!(*calls.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_observe_once_fires_only_once() {
+        let (state, set_state) = S::new_state(Some(0));
+        let observed = Arc::new(Mutex::new(Vec::new()));
+
+        let observed_clone = observed.clone();
+        state.observe_once(move |value| observed_clone.lock().unwrap().push(*value)).unwrap();
+
+        set_state(Some(1)).unwrap();
+        set_state(Some(2)).unwrap();
+        assert_eq!(*observed.lock().unwrap(), vec![Some(1)]);
+    }
+
+    #[test]
+    fn test_on_change_diff_sees_previous_and_current() {
+        let (state, set_state) = S::new_state(None);
+        let diffs = Arc::new(Mutex::new(Vec::new()));
+
+        let diffs_clone = diffs.clone();
+        let _subscription = state.on_change_diff(move |previous: Option<&i32>, current: Option<&i32>| {
+            diffs_clone.lock().unwrap().push((previous.copied(), current.copied()));
+        }).unwrap();
+
+        set_state(Some(1)).unwrap();
+        set_state(Some(2)).unwrap();
+        // Первая запись в пустое состояние => предыдущее значение None
+        assert_eq!(*diffs.lock().unwrap(), vec![(None, Some(1)), (Some(1), Some(2))]);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_watch_initial_value() {
+        let (state, _) = S::new_state(Some(42));
+        let receiver = state.watch();
+        assert_eq!(*receiver.borrow(), Some(42));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_watch_receives_set() {
+        let (state, set_state) = S::new_state(Some(1));
+        let mut receiver = state.watch();
+
+        let waiter = tokio::spawn(async move {
+            receiver.changed().await.unwrap();
+            *receiver.borrow_and_update()
+        });
+        set_state(Some(2)).unwrap();
+
+        assert_eq!(waiter.await.unwrap(), Some(2));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_watch_receivers_share_sender() {
+        let (state, set_state) = S::new_state(Some(1));
+        let mut first = state.watch();
+        let mut second = state.watch();
+
+        set_state(Some(2)).unwrap();
+        first.changed().await.unwrap();
+        second.changed().await.unwrap();
+        assert_eq!(*first.borrow_and_update(), Some(2));
+        assert_eq!(*second.borrow_and_update(), Some(2));
+
+        // Отправитель принадлежит состоянию и удаляется вместе с ним и сеттером
+        drop(state);
+        drop(set_state);
+        assert!(first.changed().await.is_err());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_dropped_receiver_does_not_break_setter() {
+        let (state, set_state) = S::new_state(Some(1));
+        let receiver = state.watch();
+        drop(receiver);
+
+        set_state(Some(2)).unwrap();
+        set_state(None).unwrap();
+        let receiver = state.watch();
+        assert_eq!(*receiver.borrow(), None);
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn test_changes_stream() {
+        use futures::StreamExt;
+
+        let (state, set_state) = S::new_state(Some(0));
+        let changes = state.changes();
+
+        set_state(Some(1)).unwrap();
+        set_state(None).unwrap();
+        set_state(Some(3)).unwrap();
+        // Поток завершается, когда состояние и сеттер удалены
+        drop(state);
+        drop(set_state);
+
+        let values: Vec<Option<i32>> = futures::executor::block_on(changes.collect());
+        assert_eq!(values, vec![Some(1), None, Some(3)]);
+    }
+}
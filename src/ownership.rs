@@ -0,0 +1,231 @@
+use alloc::sync::Arc;
+
+use crate::error::{self, StateError};
+use crate::lock;
+use crate::State;
+
+/// Takes the value out of a state, consuming it.
+///
+/// This succeeds only if `state` is the last strong reference to the value: every other clone
+/// of the state and every setter writing to it must be dropped first. Weak references do not
+/// prevent it. Unlike reading the value, this does not clone it.
+///
+/// # Arguments
+///
+/// * `state` - The state to take apart.
+///
+/// # Return Value
+///
+/// Returns the value held by the state, `StateError::Shared` with the number of other strong
+/// references if there are any, or `StateError::Poisoned` if the lock is poisoned.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{into_inner, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, set_state) = Buffer::new_state(Some(vec![1, 2, 3]));
+/// drop(set_state);
+/// assert_eq!(into_inner(state.into_state()).unwrap(), Some(vec![1, 2, 3]));
+/// ```
+pub fn into_inner<T>(state: State<T>) -> error::Result<Option<T>> {
+    match Arc::try_unwrap(state) {
+        Ok(lock) => lock::into_inner(lock),
+        Err(state) => Err(StateError::Shared(strong_count(&state) - 1)),
+    }
+}
+
+/// Returns the number of strong references to the value of a state.
+///
+/// Every clone of the state counts, and so does every setter writing to it, including the one
+/// returned by `StateManager::new_state`. Observers holding a clone count as well. This helps to
+/// find out why `into_inner` fails.
+///
+/// # Arguments
+///
+/// * `state` - The state to inspect.
+///
+/// # Return Value
+///
+/// Returns the number of strong references, including `state` itself.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{strong_count, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, set_state) = Buffer::new_state(Some(1));
+/// assert_eq!(strong_count(&state), 2);
+/// drop(set_state);
+/// assert_eq!(strong_count(&state), 1);
+/// ```
+pub fn strong_count<T>(state: &State<T>) -> usize {
+    Arc::strong_count(state)
+}
+
+/// Returns the number of weak references to the value of a state.
+///
+/// Every `WeakState` created by `downgrade` counts, as do the weak references kept internally,
+/// e.g. by derived states.
+///
+/// # Arguments
+///
+/// * `state` - The state to inspect.
+///
+/// # Return Value
+///
+/// Returns the number of weak references.
+pub fn weak_count<T>(state: &State<T>) -> usize {
+    Arc::weak_count(state)
+}
+
+/// Returns mutable access to the value of a state which is not shared, without locking it.
+///
+/// Locking is unnecessary while `state` is the only reference to the value: there must be no
+/// other clone of the state, no setter writing to it, and no weak reference to it. This suits
+/// single-owner phases, such as building the value before handing the state out.
+///
+/// This is `Arc::get_mut` followed by `RwLock::get_mut`, so it takes a bare `State`, e.g. one
+/// taken out of its `ManagedState` with `into_state`. The observers and the history of the
+/// managed state hold no reference to the value, so they do not make it shared, but changes made
+/// through the returned reference are not published to them.
+///
+/// # Arguments
+///
+/// * `state` - The state to access.
+///
+/// # Return Value
+///
+/// Returns the mutable reference to the stored `Option<T>`, or `None` if the state is shared or
+/// its lock is poisoned.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{get_mut, Getter, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, set_state) = Buffer::new_state(Some(vec![1]));
+/// drop(set_state);
+/// let mut state = state.into_state();
+/// get_mut(&mut state).unwrap().as_mut().unwrap().push(2);
+/// assert_eq!(state.get(), Some(vec![1, 2]));
+/// ```
+pub fn get_mut<T>(state: &mut State<T>) -> Option<&mut Option<T>> {
+    lock::get_mut(Arc::get_mut(state)?)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::error::StateError;
+    use crate::tests::S;
+    use crate::{downgrade, get_mut, into_inner, strong_count, weak_count, Getter, StateManager};
+
+    #[test]
+    fn test_into_inner_last_reference() {
+        let (state, set_state) = S::new_state(Some(String::from("owned")));
+        drop(set_state);
+        assert_eq!(into_inner(state.into_state()), Ok(Some(String::from("owned"))));
+    }
+
+    #[test]
+    fn test_into_inner_outstanding_references() {
+        let (state, set_state) = S::new_state(Some(1));
+        let state_clone = state.clone();
+        drop(set_state);
+
+        // Клон удерживает значение
+        assert_eq!(into_inner(state.into_state()), Err(StateError::Shared(1)));
+        assert_eq!(into_inner(state_clone.into_state()), Ok(Some(1)));
+    }
+
+    #[test]
+    fn test_reference_counts() {
+        let (state, set_state) = S::new_state(Some(1));
+        // Сеттер тоже держит сильную ссылку
+        assert_eq!(strong_count(&state), 2);
+        assert_eq!(weak_count(&state), 0);
+
+        let clones = vec![state.clone(), state.clone(), state.clone()];
+        let weak = downgrade(&state);
+        let weak_clone = weak.clone();
+        assert_eq!(strong_count(&state), 5);
+        assert_eq!(weak_count(&state), 2);
+
+        drop(clones);
+        drop(set_state);
+        drop(weak);
+        assert_eq!(strong_count(&state), 1);
+        assert_eq!(weak_count(&state), 1);
+        drop(weak_clone);
+    }
+
+    #[test]
+    fn test_get_mut_unique_state() {
+        let (state, set_state) = S::new_state(Some(1));
+        drop(set_state);
+        let mut state = state.into_state();
+
+        *get_mut(&mut state).unwrap() = Some(2);
+        assert_eq!(state.get(), Some(2));
+    }
+
+    #[test]
+    fn test_get_mut_after_writes() {
+        let (state, set_state) = S::new_state(Some(1));
+        set_state(Some(2)).unwrap();
+        drop(set_state);
+        let mut state = state.into_state();
+
+        assert_eq!(weak_count(&state), 0);
+        *get_mut(&mut state).unwrap() = Some(3);
+        assert_eq!(state.get(), Some(3));
+
+        let weak = downgrade(&state);
+        assert!(get_mut(&mut state).is_none());
+        drop(weak);
+        assert!(get_mut(&mut state).is_some());
+    }
+
+    #[test]
+    fn test_get_mut_shared_state() {
+        let (state, set_state) = S::new_state(Some(1));
+        drop(set_state);
+        let mut state = state.into_state();
+        let state_clone = state.clone();
+        assert!(get_mut(&mut state).is_none());
+
+        // Слабая ссылка тоже мешает эксклюзивному доступу
+        drop(state_clone);
+        let weak = downgrade(&state);
+        assert!(get_mut(&mut state).is_none());
+        drop(weak);
+        assert!(get_mut(&mut state).is_some());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_get_mut_after_subscribing() {
+        use crate::Observable;
+
+        let (state, set_state) = S::new_history_state(Some(1), 4);
+        let receiver = state.subscribe();
+        set_state(Some(2)).unwrap();
+        drop(set_state);
+
+        // Наблюдатели и история не держат ссылок на значение
+        let mut state = state.into_state();
+        assert_eq!(weak_count(&state), 0);
+        *get_mut(&mut state).unwrap() = Some(3);
+        assert_eq!(receiver.recv().unwrap(), Some(2));
+        assert_eq!(state.get(), Some(3));
+    }
+}
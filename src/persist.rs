@@ -0,0 +1,168 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{error, State};
+
+/// The `Persist` trait provides one-call persistence of the state to a JSON file.
+///
+/// # Examples
+///
+/// ```no_run
+/// use state_manager::{Getter, Persist, State, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, _) = Buffer::new_state(Some(42));
+/// state.save_to_path("state.json").unwrap();
+///
+/// let restored = State::<i32>::load_from_path("state.json").unwrap();
+/// assert_eq!(restored.get(), Some(42));
+/// ```
+pub trait Persist<T>: Sized {
+    /// Serializes the current value of the state to the file at `path`, replacing its contents.
+    ///
+    /// The value is first written to a temporary file next to `path`, which is then renamed over
+    /// it, so a failed or interrupted save leaves the previous contents of the file intact.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to write.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` once the file is written, `StateError::Serde` if the value could not be
+    /// serialized, or `StateError::Io` if the file could not be written.
+    fn save_to_path<P: AsRef<Path>>(&self, path: P) -> error::Result<()>;
+
+    /// Reconstructs a state from the file at `path`.
+    ///
+    /// A file representing `None` yields an empty state.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to read.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the fresh state, `StateError::Io` if the file could not be read, or
+    /// `StateError::Serde` if its contents could not be deserialized.
+    fn load_from_path<P: AsRef<Path>>(path: P) -> error::Result<Self>;
+}
+
+impl<T> Persist<T> for State<T>
+where T: Serialize + DeserializeOwned
+{
+    fn save_to_path<P: AsRef<Path>>(&self, path: P) -> error::Result<()> {
+        let json = serde_json::to_string(self)?;
+        let path = path.as_ref();
+        let temp_path = temp_path_for(path)?;
+        if let Err(e) = fs::write(&temp_path, json).and_then(|_| fs::rename(&temp_path, path)) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    fn load_from_path<P: AsRef<Path>>(path: P) -> error::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+/// Returns the path of a temporary file in the directory of `path`, unique within the process,
+/// so that renaming it over `path` never crosses a file system.
+fn temp_path_for(path: &Path) -> io::Result<std::path::PathBuf> {
+    // Счётчик различает одновременные сохранения в один и тот же файл
+    static SAVES: AtomicUsize = AtomicUsize::new(0);
+    let Some(file_name) = path.file_name() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "the path does not name a file"));
+    };
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(".{}.{}.tmp", std::process::id(), SAVES.fetch_add(1, Ordering::Relaxed)));
+    Ok(path.with_file_name(temp_name))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use crate::error::StateError;
+    use crate::tests::S;
+    use crate::{Getter, Persist, State, StateManager};
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("state-manager-{}-{}.json", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_save_and_load_some() {
+        let path = temp_path("some");
+        let (state, _) = S::new_state(Some(vec![1, 2, 3]));
+        state.save_to_path(&path).unwrap();
+
+        let restored = State::<Vec<i32>>::load_from_path(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(restored.get(), Some(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_save_and_load_none() {
+        let path = temp_path("none");
+        let (state, _) = S::new_state(None::<i32>);
+        state.save_to_path(&path).unwrap();
+
+        let restored = State::<i32>::load_from_path(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(restored.get(), None);
+    }
+
+    #[test]
+    fn test_save_replaces_contents() {
+        let path = temp_path("replace");
+        fs::write(&path, "[1,2,3,4,5,6,7,8,9]").unwrap();
+        let (state, _) = S::new_state(Some(vec![1]));
+        state.save_to_path(&path).unwrap();
+
+        let restored = State::<Vec<i32>>::load_from_path(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(restored.get(), Some(vec![1]));
+        // Временный файл не остаётся рядом с целевым
+        let temp_prefix = format!(".{}", path.file_name().unwrap().to_string_lossy());
+        let leftovers = fs::read_dir(path.parent().unwrap()).unwrap()
+            .filter(|entry| entry.as_ref().unwrap().file_name().to_string_lossy().starts_with(&temp_prefix))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn test_save_into_missing_directory() {
+        let path = temp_path("missing-dir").join("state.json");
+        let (state, _) = S::new_state(Some(1));
+        assert!(matches!(state.save_to_path(&path), Err(StateError::Io(_))));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let result = State::<i32>::load_from_path(temp_path("missing"));
+        assert!(matches!(result, Err(StateError::Io(_))));
+    }
+
+    #[test]
+    fn test_load_invalid_contents() {
+        let path = temp_path("invalid");
+        fs::write(&path, "not json").unwrap();
+
+        let result = State::<i32>::load_from_path(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(StateError::Serde(_))));
+    }
+}
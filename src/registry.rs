@@ -0,0 +1,347 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use crate::{error, Getter, ManagedState, StateExt};
+
+/// A container holding at most one state of every type.
+///
+/// The registry acts as a service locator for applications with many independent states:
+/// a state is registered once and can then be looked up by its value type from anywhere
+/// the registry is reachable. Lookups return clones of the stored `ManagedState`, so all of them
+/// share the same value.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Getter, StateBuffer, StateManager, StateRegistry};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let registry = StateRegistry::new();
+/// let (count, _) = Buffer::new_state(Some(1));
+/// registry.insert(count);
+///
+/// let count = registry.get::<i32>().unwrap();
+/// assert_eq!(count.get(), Some(1));
+/// assert!(registry.get::<String>().is_none());
+/// ```
+#[derive(Default)]
+pub struct StateRegistry {
+    states: RwLock<HashMap<TypeId, Box<dyn Any + Send + Sync>>>,
+}
+
+impl StateRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        StateRegistry::default()
+    }
+
+    /// Registers `state` as the state of type `T`, replacing the previously registered one.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to register.
+    ///
+    /// With the `tracing` feature enabled, replacing a registered state logs a warning.
+    pub fn insert<T>(&self, state: ManagedState<T>)
+        where T: Send + Sync + 'static
+    {
+        let _previous = self.states
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(TypeId::of::<T>(), Box::new(state));
+        #[cfg(feature = "tracing")]
+        if _previous.is_some() {
+            tracing::warn!(state = std::any::type_name::<T>(), "replaced a registered state");
+        }
+    }
+
+    /// Registers a new state holding `T::default()` as the state of type `T`.
+    ///
+    /// Like `insert`, this replaces the previously registered state of type `T`. To register
+    /// the default states of several types at startup, use the `register_defaults!` macro.
+    pub fn from_defaults<T>(&self)
+        where T: Default + Send + Sync + 'static
+    {
+        self.insert(ManagedState::configured(Some(T::default()), None, true, None));
+    }
+
+    /// Returns the state of type `T`.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a clone of the registered `ManagedState<T>`, or `None` if no state of type `T` was registered.
+    pub fn get<T>(&self) -> Option<ManagedState<T>>
+        where T: 'static
+    {
+        self.states
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&TypeId::of::<T>())
+            .and_then(|state| state.downcast_ref::<ManagedState<T>>())
+            .cloned()
+    }
+
+    /// Returns the state of type `T`, registering a state holding `T::default()` if there is none.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a clone of the registered `ManagedState<T>`.
+    pub fn get_or_create<T>(&self) -> ManagedState<T>
+        where T: Default + Clone + Send + Sync + 'static
+    {
+        let mut states = self.states.write().unwrap_or_else(PoisonError::into_inner);
+        states
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(ManagedState::configured(Some(T::default()), None, true, None)))
+            .downcast_ref::<ManagedState<T>>()
+            .cloned()
+            .expect("state registered under the TypeId of another type")
+    }
+}
+
+/// Registers a state holding the default value of every listed type in a `StateRegistry`.
+///
+/// This is the startup counterpart of `StateRegistry::from_defaults`, much like the setup of a
+/// dependency-injection container. Types which are already registered are replaced.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{register_defaults, Getter, StateRegistry};
+///
+/// let registry = StateRegistry::new();
+/// register_defaults!(registry, i32, String, Vec<u8>);
+///
+/// assert_eq!(registry.get::<i32>().unwrap().get(), Some(0));
+/// assert_eq!(registry.get::<Vec<u8>>().unwrap().get(), Some(vec![]));
+/// ```
+#[macro_export]
+macro_rules! register_defaults {
+    ($registry:expr, $($T:ty),+ $(,)?) => {{
+        let registry: &$crate::StateRegistry = &$registry;
+        $(registry.from_defaults::<$T>();)+
+    }};
+}
+
+/// A container of states of the same type distinguished by name.
+///
+/// Unlike `StateRegistry`, which holds one state per type, the store keeps any number of
+/// states of type `T`, e.g. one counter per user. Cloning the store is cheap: all clones
+/// share the same states.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::NamedStateStore;
+///
+/// let counters = NamedStateStore::new();
+/// counters.set("alice", Some(1)).unwrap();
+/// counters.set("bob", Some(2)).unwrap();
+///
+/// assert_eq!(counters.get("alice"), Some(1));
+/// assert_eq!(counters.get("carol"), None);
+/// ```
+pub struct NamedStateStore<T> {
+    states: Arc<RwLock<HashMap<String, ManagedState<T>>>>,
+}
+
+impl<T> NamedStateStore<T> {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        NamedStateStore {
+            states: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Stores `value` under `key`.
+    ///
+    /// If a state with this name already exists, its value is replaced and its observers are
+    /// notified. Otherwise a new state is created.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the state.
+    /// * `value` - The new value of the state.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` once the value is stored, or a `StateError` if the lock of the existing
+    /// state could not be acquired.
+    pub fn set(&self, key: &str, value: Option<T>) -> error::Result<()>
+        where T: Send + Sync + 'static
+    {
+        let existing = self.states
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(key)
+            .cloned();
+        match existing {
+            Some(state) => state.set(value),
+            None => {
+                let mut states = self.states.write().unwrap_or_else(PoisonError::into_inner);
+                // Состояние могло быть создано другим потоком между блокировками
+                match states.get(key) {
+                    Some(state) => state.set(value),
+                    None => {
+                        states.insert(key.to_owned(), ManagedState::configured(value, None, true, None));
+                        Ok(())
+                    },
+                }
+            },
+        }
+    }
+
+    /// Returns the value of the state named `key`.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the current value, or `None` if the state is empty or there is no state named `key`.
+    pub fn get(&self, key: &str) -> Option<T>
+        where T: Clone
+    {
+        self.states
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(key)
+            .and_then(|state| state.get())
+    }
+
+    /// Removes the state named `key` from the store.
+    ///
+    /// Clones of the removed state obtained earlier keep working but are no longer reachable
+    /// through the store.
+    pub fn remove(&self, key: &str) {
+        self.states
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(key);
+    }
+}
+
+impl<T> Clone for NamedStateStore<T> {
+    fn clone(&self) -> Self {
+        NamedStateStore {
+            states: self.states.clone(),
+        }
+    }
+}
+
+impl<T> Default for NamedStateStore<T> {
+    fn default() -> Self {
+        NamedStateStore::new()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::tests::S;
+    use crate::{Getter, NamedStateStore, StateExt, StateManager, StateRegistry};
+
+    #[test]
+    fn test_insert_two_types() {
+        let registry = StateRegistry::new();
+        let (count, _) = S::new_state(Some(1));
+        let (name, _) = S::new_state(Some(String::from("state")));
+        registry.insert(count);
+        registry.insert(name);
+
+        assert_eq!(registry.get::<i32>().unwrap().get(), Some(1));
+        assert_eq!(registry.get::<String>().unwrap().get(), Some(String::from("state")));
+    }
+
+    #[test]
+    fn test_get_missing_type() {
+        let registry = StateRegistry::new();
+        assert!(registry.get::<u64>().is_none());
+    }
+
+    #[test]
+    fn test_get_shares_value() {
+        let registry = StateRegistry::new();
+        let (count, set_count) = S::new_state(Some(1));
+        registry.insert(count);
+
+        // Реестр хранит тот же экземпляр состояния
+        set_count(Some(2)).unwrap();
+        assert_eq!(registry.get::<i32>().unwrap().get(), Some(2));
+    }
+
+    #[test]
+    fn test_register_defaults() {
+        let registry = StateRegistry::new();
+        register_defaults!(registry, u8, String, Vec<i32>);
+
+        assert_eq!(registry.get::<u8>().unwrap().get(), Some(0));
+        assert_eq!(registry.get::<String>().unwrap().get(), Some(String::new()));
+        assert_eq!(registry.get::<Vec<i32>>().unwrap().get(), Some(vec![]));
+    }
+
+    #[test]
+    fn test_from_defaults_replaces_registered_state() {
+        let registry = StateRegistry::new();
+        let (count, _) = S::new_state(Some(5));
+        registry.insert(count.clone());
+
+        // Повторная регистрация заменяет состояние
+        registry.from_defaults::<i32>();
+        assert_eq!(registry.get::<i32>().unwrap().get(), Some(0));
+        assert_eq!(count.get(), Some(5));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tracing_test::traced_test]
+    #[test]
+    fn test_replacing_logs_warning() {
+        let registry = StateRegistry::new();
+        registry.from_defaults::<i32>();
+        assert!(!logs_contain("replaced a registered state"));
+        registry.from_defaults::<i32>();
+        assert!(logs_contain("replaced a registered state"));
+    }
+
+    #[test]
+    fn test_get_or_create() {
+        let registry = StateRegistry::new();
+        let created = registry.get_or_create::<Vec<i32>>();
+        assert_eq!(created.get(), Some(vec![]));
+
+        created.set(Some(vec![1])).unwrap();
+        assert_eq!(registry.get_or_create::<Vec<i32>>().get(), Some(vec![1]));
+    }
+
+    #[test]
+    fn test_named_store_multiple_keys() {
+        let store = NamedStateStore::new();
+        store.set("alice", Some(1)).unwrap();
+        store.set("bob", Some(2)).unwrap();
+        store.set("alice", Some(3)).unwrap();
+
+        assert_eq!(store.get("alice"), Some(3));
+        assert_eq!(store.get("bob"), Some(2));
+        assert_eq!(store.get("carol"), None);
+    }
+
+    #[test]
+    fn test_named_store_remove() {
+        let store = NamedStateStore::new();
+        store.set("alice", Some(1)).unwrap();
+        store.remove("alice");
+        assert_eq!(store.get("alice"), None);
+
+        // Удаление отсутствующего ключа ничего не делает
+        store.remove("alice");
+        store.set("alice", Some(2)).unwrap();
+        assert_eq!(store.get("alice"), Some(2));
+    }
+
+    #[test]
+    fn test_named_store_clones_share_states() {
+        let store = NamedStateStore::new();
+        let store_clone = store.clone();
+        store_clone.set("alice", Some(String::from("hello"))).unwrap();
+        assert_eq!(store.get("alice"), Some(String::from("hello")));
+    }
+}
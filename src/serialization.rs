@@ -0,0 +1,66 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{ManagedState, State};
+
+impl<T> Serialize for ManagedState<T>
+where T: Serialize
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        State::serialize(self, serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for ManagedState<T>
+where T: Deserialize<'de>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        State::deserialize(deserializer).map(ManagedState::new)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::tests::S;
+    use crate::{Getter, ManagedState, State, StateManager};
+
+    #[test]
+    fn test_round_trip_some() {
+        let (state, _) = S::new_state(Some(String::from("value")));
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(json, "\"value\"");
+
+        let restored: State<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.get(), Some(String::from("value")));
+    }
+
+    #[test]
+    fn test_round_trip_none() {
+        let (state, _) = S::new_state(None::<i32>);
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(json, "null");
+
+        let restored: State<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.get(), None);
+    }
+
+    #[test]
+    fn test_round_trip_managed_state() {
+        let (state, _) = S::new_state(Some(String::from("value")));
+        let json = serde_json::to_string(&state).unwrap();
+
+        let restored: ManagedState<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.get(), Some(String::from("value")));
+        // Восстановленное состояние имеет собственный сеттер
+        restored.setter()(None).unwrap();
+        assert_eq!(restored.get(), None);
+    }
+
+    #[test]
+    fn test_deserialized_state_is_independent() {
+        let (state, set_state) = S::new_state(Some(1));
+        let restored: State<i32> = serde_json::from_str(&serde_json::to_string(&state).unwrap()).unwrap();
+
+        set_state(Some(2)).unwrap();
+        assert_eq!(restored.get(), Some(1));
+    }
+}
@@ -0,0 +1,221 @@
+use crate::error::StateError;
+use crate::{error, lock, StateExt};
+
+/// The `Setter` trait provides additional ways of writing a value to the state,
+/// complementing the `StateSetter` returned by `StateManager::new_state`.
+///
+/// Like the `StateSetter`, its methods return `StateError::Frozen` once the state is frozen.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Getter, Setter, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, _) = Buffer::new_state(Some(1));
+/// state.set_recovering(Some(2)).unwrap();
+/// assert_eq!(state.get(), Some(2));
+/// ```
+pub trait Setter<T> {
+    /// Writes the new value even if the lock is poisoned, clearing the poison flag.
+    ///
+    /// Once a thread panics while holding the write lock, every ordinary write fails.
+    /// This method recovers the guard from the `PoisonError`, stores `data` and marks the
+    /// lock as healthy again, so long-running services can survive a panic in one updater.
+    ///
+    /// Note that the value left behind by the panicking thread may be inconsistent.
+    /// Ensuring data consistency after poisoning is the caller's responsibility: `data`
+    /// completely replaces whatever the state held.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The new value of the state.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` once the value is written.
+    fn set_recovering(&self, data: Option<T>) -> error::Result<()>;
+
+    /// Stores `data` only if the state is currently empty.
+    ///
+    /// The check and the store happen under a single write lock, so when several threads
+    /// race to initialize the state, exactly one of them wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The value to initialize the state with.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(true)` if the value was stored, `Ok(false)` if the state already held a value,
+    /// or a `StateError` if the lock could not be acquired.
+    fn set_if_none(&self, data: T) -> error::Result<bool>;
+
+    /// Restores the state to the default value of `T`.
+    ///
+    /// Unlike `Swapper::take`, which leaves the state empty (`None`), the state holds
+    /// `Some(T::default())` afterwards, so readers still see a value.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` once the default value is written, or a `StateError` if the lock
+    /// could not be acquired.
+    fn reset(&self) -> error::Result<()>
+        where T: Default;
+
+    /// Writes `data` to the state, handing it back if the write fails.
+    ///
+    /// This behaves like the `StateSetter` returned by `StateManager::new_state`, including
+    /// recording the history, but on failure the rejected value is returned alongside the error,
+    /// like `std::sync::mpsc::SyncSender::try_send` does. The caller can then retry or recover
+    /// without reconstructing the value.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The new value of the state.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` once the value is written, or the value together with the `StateError`
+    /// that prevented the write.
+    fn try_set(&self, data: Option<T>) -> Result<(), (Option<T>, StateError)>;
+}
+
+impl<T, S> Setter<T> for S
+where S: StateExt<T>
+{
+    fn set_recovering(&self, data: Option<T>) -> error::Result<()> {
+        let mut state_guard = lock::write_recovering(self.state());
+        if self.is_frozen_locked() {
+            return Err(StateError::Frozen);
+        }
+        *state_guard = data;
+        self.publish(state_guard);
+        Ok(())
+    }
+
+    fn set_if_none(&self, data: T) -> error::Result<bool> {
+        let mut state_guard = self.write_for_setter()?;
+        if state_guard.is_some() {
+            return Ok(false);
+        }
+        *state_guard = Some(data);
+        self.publish(state_guard);
+        Ok(true)
+    }
+
+    fn reset(&self) -> error::Result<()>
+        where T: Default
+    {
+        let mut state_guard = self.write_for_setter()?;
+        *state_guard = Some(T::default());
+        self.publish(state_guard);
+        Ok(())
+    }
+
+    fn try_set(&self, data: Option<T>) -> Result<(), (Option<T>, StateError)> {
+        self.store(data)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::thread;
+
+    #[cfg(all(feature = "std", not(feature = "parking-lot")))]
+    use crate::error::StateError;
+    use crate::tests::S;
+    use crate::{Getter, Setter, StateManager, Swapper};
+
+    #[test]
+    fn test_set_recovering_healthy_lock() {
+        let (state, _) = S::new_state(None);
+        state.set_recovering(Some(42)).unwrap();
+        assert_eq!(state.get(), Some(42));
+    }
+
+    #[cfg(all(feature = "std", not(feature = "parking-lot")))]
+    #[test]
+    fn test_set_recovering_poisoned_lock() {
+        let (state, set_state) = S::new_state(Some(1));
+        let state_for_panic = state.clone();
+        let _ = thread::spawn(move || {
+            let _guard = state_for_panic.write().unwrap();
+            panic!("poison the lock");
+        }).join();
+        assert!(set_state(Some(2)).is_err());
+
+        state.set_recovering(Some(3)).unwrap();
+        assert_eq!(state.try_get_result().unwrap(), Some(3));
+        // После восстановления обычный сеттер снова работает
+        set_state(Some(4)).unwrap();
+        assert_eq!(state.get(), Some(4));
+    }
+
+    #[test]
+    fn test_set_if_none() {
+        let (state, _) = S::new_state(None);
+        assert!(state.set_if_none(1).unwrap());
+        assert!(!state.set_if_none(2).unwrap());
+        assert_eq!(state.get(), Some(1));
+    }
+
+    #[test]
+    fn test_set_if_none_single_winner() {
+        let (state, _) = S::new_state(None);
+        let mut handles = vec![];
+
+        for id in 0..8 {
+            let state_clone = state.clone();
+            handles.push(thread::spawn(move || state_clone.set_if_none(id).unwrap()));
+        }
+
+        let winners: Vec<bool> = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .filter(|won| *won)
+            .collect();
+        // Ровно один поток инициализирует состояние
+        assert_eq!(winners.len(), 1);
+        assert!(state.get().is_some());
+    }
+
+    #[test]
+    fn test_reset_to_default() {
+        let (state, _) = S::new_state(Some(vec![1, 2, 3]));
+        state.reset().unwrap();
+        assert_eq!(state.get(), Some(Vec::<i32>::default()));
+
+        // В отличие от take, reset оставляет значение
+        state.take().unwrap();
+        state.reset().unwrap();
+        assert_eq!(state.get(), Some(vec![]));
+    }
+
+    #[test]
+    fn test_try_set_healthy_lock() {
+        let (state, _) = S::new_state(Some(1));
+        state.try_set(Some(2)).unwrap();
+        assert_eq!(state.get(), Some(2));
+    }
+
+    #[cfg(all(feature = "std", not(feature = "parking-lot")))]
+    #[test]
+    fn test_try_set_returns_value_on_poisoned_lock() {
+        let (state, _) = S::new_state(Some(vec![1]));
+        let state_for_panic = state.clone();
+        let _ = thread::spawn(move || {
+            let _guard = state_for_panic.write().unwrap();
+            panic!("poison the lock");
+        }).join();
+
+        let (value, err) = state.try_set(Some(vec![1, 2, 3])).unwrap_err();
+        assert!(matches!(err, StateError::Poisoned));
+        // Значение возвращено вызывающему и может быть записано повторно
+        assert_eq!(value, Some(vec![1, 2, 3]));
+        state.set_recovering(value).unwrap();
+        assert_eq!(state.get(), Some(vec![1, 2, 3]));
+    }
+}
@@ -0,0 +1,67 @@
+use alloc::sync::Arc;
+
+use crate::{lock, State};
+
+/// The `ArcGetter` trait provides cheap shared reads of a value stored in an `Arc`.
+///
+/// `Getter::get` already clones an `Arc` cheaply, but `get_arc` makes the intent explicit:
+/// the caller receives a handle to the same allocation rather than a copy of the value.
+/// Storing large, rarely changing values as `State<Arc<T>>` lets many readers share them
+/// without holding the lock while they work with the value.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use state_manager::{ArcGetter, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (config, _) = Buffer::new_state(Some(Arc::new(vec![1, 2, 3])));
+/// let shared = config.get_arc().unwrap();
+/// assert_eq!(shared.len(), 3);
+/// ```
+pub trait ArcGetter<T> {
+    /// Returns a clone of the stored `Arc`, pointing to the same allocation as the state.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Some` with the `Arc`, or `None` if the state is empty or the lock is poisoned.
+    fn get_arc(&self) -> Option<Arc<T>>;
+}
+
+impl<T> ArcGetter<T> for State<Arc<T>> {
+    fn get_arc(&self) -> Option<Arc<T>> {
+        match lock::read(self) {
+            Ok(state_guard) => state_guard.as_ref().map(Arc::clone),
+            Err(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use alloc::sync::Arc;
+
+    use crate::tests::S;
+    use crate::{ArcGetter, StateManager};
+
+    #[test]
+    fn test_get_arc_shares_allocation() {
+        let (state, _) = S::new_state(Some(Arc::new(String::from("shared"))));
+        let first = state.get_arc().unwrap();
+        let second = state.get_arc().unwrap();
+
+        assert!(Arc::ptr_eq(&first, &second));
+        // Состояние и две копии указывают на одно значение
+        assert_eq!(Arc::strong_count(&first), 3);
+    }
+
+    #[test]
+    fn test_get_arc_empty_state() {
+        let (state, _) = S::new_state(None::<Arc<i32>>);
+        assert_eq!(state.get_arc(), None);
+    }
+}
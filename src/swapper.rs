@@ -0,0 +1,358 @@
+use core::mem;
+
+use crate::{error, lock, StateExt};
+
+/// The `Swapper` trait provides atomic exchange of the value stored in the state.
+///
+/// All operations of this trait are performed under a single write lock, so no
+/// intermediate state can be observed by other threads.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Getter, StateBuffer, StateManager, Swapper};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (batch, _) = Buffer::new_state(Some(vec![1, 2, 3]));
+/// let previous = batch.swap(None).unwrap();
+/// assert_eq!(previous, Some(vec![1, 2, 3]));
+/// assert_eq!(batch.get(), None);
+/// ```
+pub trait Swapper<T> {
+    /// Stores the new value and returns the one that was there before.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The new value of the state.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the previous value of the state, or a `StateError` if the lock could not be acquired.
+    fn swap(&self, data: Option<T>) -> error::Result<Option<T>>;
+
+    /// Clears the state and returns the value it held.
+    ///
+    /// This is equivalent to `swap(None)` and allows draining the state in one atomic step.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the previous value of the state, or a `StateError` if the lock could not be acquired
+    /// (for example, when it is poisoned).
+    fn take(&self) -> error::Result<Option<T>>;
+
+    /// Stores `new` only if the current value equals `expected`.
+    ///
+    /// The comparison and the store happen under a single write lock, which allows
+    /// callers to build optimistic retry loops without external synchronization.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected` - The value the state is expected to hold.
+    /// * `new` - The value to store if the expectation holds.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(true)` if the value was stored, `Ok(false)` if the state held a different value
+    /// and was left untouched, or a `StateError` if the lock could not be acquired.
+    fn compare_and_swap(&self, expected: Option<T>, new: Option<T>) -> error::Result<bool>
+        where T: PartialEq;
+
+    /// Stores `Some(data)` and returns the value it replaced, without dealing in `Option`s.
+    ///
+    /// Unlike `swap`, the previous value is returned unwrapped: if the state was empty,
+    /// `T::default()` is returned instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The new value of the state.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the previous value, or `T::default()` if the state was empty, or a `StateError`
+    /// if the lock could not be acquired.
+    fn replace(&self, data: T) -> error::Result<T>
+        where T: Default;
+
+    /// Exchanges the values of this state and `other`, e.g. the front and back buffers of
+    /// double buffering.
+    ///
+    /// Both states are locked for writing at the same time, so no reader can observe the same
+    /// value in both of them. To avoid a deadlock when two threads swap the same states in
+    /// opposite order, the locks are always acquired in the order of the addresses of the shared
+    /// state data, like `Access::content_eq` does. The observers of both states are notified
+    /// once both locks are released. Swapping a state with itself or one of its clones does
+    /// nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The state to exchange the value with.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` once the values are exchanged, or a `StateError` if either lock could not
+    /// be acquired, in which case neither state is changed.
+    fn swap_with(&self, other: &Self) -> error::Result<()>;
+}
+
+impl<T, S> Swapper<T> for S
+where S: StateExt<T>
+{
+    fn swap(&self, data: Option<T>) -> error::Result<Option<T>> {
+        let mut state_guard = self.write_for_setter()?;
+        let previous = mem::replace(&mut *state_guard, data);
+        self.publish(state_guard);
+        Ok(previous)
+    }
+
+    fn take(&self) -> error::Result<Option<T>> {
+        let mut state_guard = self.write_for_setter()?;
+        let previous = state_guard.take();
+        self.publish(state_guard);
+        Ok(previous)
+    }
+
+    fn compare_and_swap(&self, expected: Option<T>, new: Option<T>) -> error::Result<bool>
+        where T: PartialEq
+    {
+        let mut state_guard = self.write_for_setter()?;
+        if *state_guard != expected {
+            return Ok(false);
+        }
+        *state_guard = new;
+        self.publish(state_guard);
+        Ok(true)
+    }
+
+    fn replace(&self, data: T) -> error::Result<T>
+        where T: Default
+    {
+        Ok(self.swap(Some(data))?.unwrap_or_default())
+    }
+
+    fn swap_with(&self, other: &Self) -> error::Result<()> {
+        if lock::address(self.state()) == lock::address(other.state()) {
+            return Ok(());
+        }
+
+        let (first, second) = if lock::address(self.state()) < lock::address(other.state()) {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut first_guard = first.write_for_setter()?;
+        let mut second_guard = second.write_for_setter()?;
+        mem::swap(&mut *first_guard, &mut *second_guard);
+
+        // Уведомляем только после освобождения обеих блокировок
+        first.prepare_publish(&first_guard);
+        second.prepare_publish(&second_guard);
+        drop(second_guard);
+        drop(first_guard);
+        first.complete_publish();
+        second.complete_publish();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::thread;
+
+    #[cfg(all(feature = "std", not(feature = "parking-lot")))]
+    use crate::error::StateError;
+    use crate::tests::S;
+    use crate::{Getter, StateManager, Swapper};
+
+    #[test]
+    fn test_swap_some_to_none() {
+        let (state, _) = S::new_state(Some(42));
+        assert_eq!(state.swap(None).unwrap(), Some(42));
+        assert_eq!(state.get(), None);
+    }
+
+    #[test]
+    fn test_swap_none_to_some() {
+        let (state, _) = S::new_state(None::<i32>);
+        assert_eq!(state.swap(Some(42)).unwrap(), None);
+        assert_eq!(state.get(), Some(42));
+    }
+
+    #[test]
+    fn test_swap_back_and_forth() {
+        let (state, _) = S::new_state(Some(1));
+        assert_eq!(state.swap(None).unwrap(), Some(1));
+        assert_eq!(state.swap(Some(2)).unwrap(), None);
+        assert_eq!(state.swap(Some(3)).unwrap(), Some(2));
+        assert_eq!(state.get(), Some(3));
+    }
+
+    #[test]
+    fn test_take_clears_state() {
+        let (state, _) = S::new_state(Some(42));
+        assert_eq!(state.take().unwrap(), Some(42));
+        assert_eq!(state.get(), None);
+        assert_eq!(state.take().unwrap(), None);
+    }
+
+    #[test]
+    fn test_take_while_reading() {
+        let (state, _) = S::new_state(Some(42));
+        let state_for_reader = state.clone();
+
+        let reader_handle = thread::spawn(move || {
+            // Читатель видит либо исходное значение, либо уже очищенное состояние
+            for _ in 0..1000 {
+                let value = state_for_reader.get();
+                assert!(value == Some(42) || value.is_none());
+            }
+        });
+        let taken = state.take().unwrap();
+
+        reader_handle.join().unwrap();
+        assert_eq!(taken, Some(42));
+        assert_eq!(state.get(), None);
+    }
+
+    #[cfg(all(feature = "std", not(feature = "parking-lot")))]
+    #[test]
+    fn test_take_poisoned_lock() {
+        let (state, _) = S::new_state(Some(42));
+        let state_for_panic = state.clone();
+        let _ = thread::spawn(move || {
+            let _guard = state_for_panic.write().unwrap();
+            panic!("poison the lock");
+        }).join();
+
+        assert!(matches!(state.take(), Err(StateError::Poisoned)));
+    }
+
+    #[test]
+    fn test_compare_and_swap_success() {
+        let (state, _) = S::new_state(Some(1));
+        assert!(state.compare_and_swap(Some(1), Some(2)).unwrap());
+        assert_eq!(state.get(), Some(2));
+    }
+
+    #[test]
+    fn test_compare_and_swap_value_changed() {
+        let (state, set_state) = S::new_state(Some(1));
+        let expected = state.get();
+        // Значение изменилось после чтения => CAS должен завершиться неудачей
+        set_state(Some(5)).unwrap();
+        assert!(!state.compare_and_swap(expected, Some(2)).unwrap());
+        assert_eq!(state.get(), Some(5));
+    }
+
+    #[test]
+    fn test_compare_and_swap_expected_none() {
+        let (state, _) = S::new_state(None::<i32>);
+        assert!(!state.compare_and_swap(Some(0), Some(1)).unwrap());
+        assert_eq!(state.get(), None);
+        assert!(state.compare_and_swap(None, Some(1)).unwrap());
+        assert_eq!(state.get(), Some(1));
+    }
+
+    #[test]
+    fn test_compare_and_swap_retry_loop() {
+        let (state, _) = S::new_state(Some(0));
+        let mut handles = vec![];
+
+        for _ in 0..4 {
+            let state_clone = state.clone();
+            handles.push(thread::spawn(move || {
+                for _ in 0..100 {
+                    loop {
+                        let current = state_clone.get();
+                        let next = current.map(|v| v + 1);
+                        if state_clone.compare_and_swap(current, next).unwrap() {
+                            break;
+                        }
+                    }
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(state.get(), Some(400));
+    }
+
+    #[test]
+    fn test_replace_over_some() {
+        let (state, _) = S::new_state(Some(String::from("old")));
+        assert_eq!(state.replace(String::from("new")).unwrap(), "old");
+        assert_eq!(state.get(), Some(String::from("new")));
+    }
+
+    #[test]
+    fn test_replace_over_none() {
+        let (state, _) = S::new_state(None::<u32>);
+        // Пустое состояние => возвращается значение по умолчанию
+        assert_eq!(state.replace(7).unwrap(), 0);
+        assert_eq!(state.get(), Some(7));
+    }
+
+    #[test]
+    fn test_swap_with_exchanges_values() {
+        let (front, _) = S::new_state(Some(vec![1]));
+        let (back, _) = S::new_state(None);
+
+        front.swap_with(&back).unwrap();
+        assert_eq!(front.get(), None);
+        assert_eq!(back.get(), Some(vec![1]));
+
+        // Обмен с самим собой ничего не меняет
+        back.swap_with(&back.clone()).unwrap();
+        assert_eq!(back.get(), Some(vec![1]));
+    }
+
+    #[test]
+    fn test_swap_with_both_directions_does_not_deadlock() {
+        let (first, _) = S::new_state(Some(1));
+        let (second, _) = S::new_state(Some(2));
+        let mut handles = vec![];
+
+        for i in 0..4 {
+            let (first, second) = (first.clone(), second.clone());
+            handles.push(thread::spawn(move || {
+                for _ in 0..250 {
+                    if i % 2 == 0 {
+                        first.swap_with(&second).unwrap();
+                    } else {
+                        second.swap_with(&first).unwrap();
+                    }
+                }
+            }));
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // Чётное число обменов => значения на месте
+        assert_eq!(first.get(), Some(1));
+        assert_eq!(second.get(), Some(2));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_swap_with_observer_reads_other_state() {
+        use std::sync::mpsc;
+
+        use crate::Observable;
+
+        let (first, _) = S::new_state(Some(1));
+        let (second, _) = S::new_state(Some(2));
+        let (sender, receiver) = mpsc::channel();
+        let second_for_observer = second.clone();
+        let _subscription = first.on_change(move |value| {
+            // Обе блокировки уже освобождены
+            sender.send((*value, second_for_observer.get())).unwrap();
+        });
+
+        first.swap_with(&second).unwrap();
+        assert_eq!(receiver.recv().unwrap(), (Some(2), Some(1)));
+    }
+}
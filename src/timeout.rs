@@ -0,0 +1,225 @@
+use std::time::Duration;
+
+use crate::{error, lock, StateExt};
+
+/// The `Timeout` trait provides reading and writing the state with a bounded wait for the lock.
+///
+/// `get_timeout` and `set_timeout` are available with the `parking-lot` feature, whose lock
+/// supports timed acquisition. `try_get_timeout` works with every `std` backend.
+/// This prevents real-time code from stalling indefinitely behind a long-running writer.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use state_manager::{StateBuffer, StateManager, Timeout};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, _) = Buffer::new_state(Some(1));
+/// assert_eq!(state.try_get_timeout(Duration::from_millis(10)).unwrap(), Some(1));
+/// ```
+pub trait Timeout<T> {
+    /// Returns the current value of the state, waiting at most `dur` for the read lock.
+    ///
+    /// # Arguments
+    ///
+    /// * `dur` - The maximum time to wait for the lock.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the current value, or `StateError::Timeout` holding `dur` if the lock could not
+    /// be acquired in time.
+    #[cfg(feature = "parking-lot")]
+    fn get_timeout(&self, dur: Duration) -> error::Result<Option<T>>
+        where T: Clone;
+
+    /// Returns the current value of the state, retrying `try_read` for at most `dur`.
+    ///
+    /// On the `std` backend this is a best-effort spin-wait: it yields the thread between
+    /// attempts, so it burns CPU while waiting and may overshoot `dur` slightly. It is less
+    /// precise than the `parking_lot` timed lock, which this defers to when that feature is on.
+    ///
+    /// # Arguments
+    ///
+    /// * `dur` - The maximum time to wait for the lock.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the current value, or `StateError::Timeout` holding `dur` if the lock could not
+    /// be acquired in time.
+    fn try_get_timeout(&self, dur: Duration) -> error::Result<Option<T>>
+        where T: Clone;
+
+    /// Stores `data`, waiting at most `dur` for the write lock.
+    ///
+    /// Apart from the bounded wait, this is the write made by the setter: it records the previous
+    /// value if the state keeps a history and fails on a frozen state.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The new value of the state.
+    /// * `dur` - The maximum time to wait for the lock.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` once the value is written, `StateError::Timeout` holding `dur` if the
+    /// lock could not be acquired in time, or `StateError::Frozen` if the state is frozen.
+    #[cfg(feature = "parking-lot")]
+    fn set_timeout(&self, data: Option<T>, dur: Duration) -> error::Result<()>;
+}
+
+impl<T, S> Timeout<T> for S
+where S: StateExt<T>
+{
+    #[cfg(feature = "parking-lot")]
+    fn get_timeout(&self, dur: Duration) -> error::Result<Option<T>>
+        where T: Clone
+    {
+        let state_guard = lock::read_for(self.state(), dur)?;
+        Ok(state_guard.clone())
+    }
+
+    fn try_get_timeout(&self, dur: Duration) -> error::Result<Option<T>>
+        where T: Clone
+    {
+        let state_guard = lock::read_spinning(self.state(), dur)?;
+        Ok(state_guard.clone())
+    }
+
+    #[cfg(feature = "parking-lot")]
+    fn set_timeout(&self, data: Option<T>, dur: Duration) -> error::Result<()> {
+        self.store_with(data, lock::Acquire::within(dur), |_| true)
+            .map(|_| ())
+            .map_err(|(_, e)| e)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::error::StateError;
+    use crate::tests::S;
+    use crate::{lock, Getter, StateManager, Timeout};
+
+    #[test]
+    #[cfg(feature = "parking-lot")]
+    fn test_timeout_free_lock() {
+        let (state, _) = S::new_state(Some(1));
+        assert_eq!(state.get_timeout(Duration::from_millis(10)).unwrap(), Some(1));
+        state.set_timeout(Some(2), Duration::from_millis(10)).unwrap();
+        assert_eq!(state.get(), Some(2));
+    }
+
+    #[test]
+    #[cfg(feature = "parking-lot")]
+    fn test_timeout_while_write_lock_is_held() {
+        let (state, _) = S::new_state(Some(1));
+        let state_for_writer = state.clone();
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        let writer_handle = thread::spawn(move || {
+            let _guard = lock::write(&state_for_writer).unwrap();
+            locked_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        match state.get_timeout(Duration::from_millis(20)) {
+            Err(StateError::Timeout(waited)) => assert_eq!(waited, Duration::from_millis(20)),
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+        assert!(matches!(
+            state.set_timeout(Some(2), Duration::from_millis(20)),
+            Err(StateError::Timeout(_))
+        ));
+
+        release_tx.send(()).unwrap();
+        writer_handle.join().unwrap();
+        assert_eq!(state.get(), Some(1));
+    }
+
+    #[test]
+    #[cfg(feature = "parking-lot")]
+    fn test_set_timeout_frozen_state() {
+        use crate::Freeze;
+
+        let (state, _) = S::new_state(Some(1));
+        state.freeze();
+        assert_eq!(state.set_timeout(Some(2), Duration::from_millis(10)), Err(StateError::Frozen));
+        assert_eq!(state.get(), Some(1));
+    }
+
+    #[test]
+    #[cfg(feature = "parking-lot")]
+    fn test_set_timeout_records_history() {
+        use crate::History;
+
+        let (state, _) = S::new_history_state(Some(1), 4);
+        state.set_timeout(Some(2), Duration::from_millis(10)).unwrap();
+        assert_eq!(state.get(), Some(2));
+        assert!(state.undo().unwrap());
+        assert_eq!(state.get(), Some(1));
+    }
+
+    #[test]
+    #[cfg(feature = "parking-lot")]
+    fn test_set_timeout_history_state_while_write_lock_is_held() {
+        let (state, _) = S::new_history_state(Some(1), 4);
+        let state_for_writer = state.clone();
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        let writer_handle = thread::spawn(move || {
+            let _guard = lock::write(&state_for_writer).unwrap();
+            locked_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        // Ожидание ограничено и при записи в состояние с историей
+        match state.set_timeout(Some(2), Duration::from_millis(20)) {
+            Err(StateError::Timeout(waited)) => assert_eq!(waited, Duration::from_millis(20)),
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+
+        release_tx.send(()).unwrap();
+        writer_handle.join().unwrap();
+        assert_eq!(state.get(), Some(1));
+    }
+
+    #[test]
+    fn test_try_get_timeout_free_lock() {
+        let (state, _) = S::new_state(Some(1));
+        assert_eq!(state.try_get_timeout(Duration::from_millis(10)).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn test_try_get_timeout_while_write_lock_is_held() {
+        let (state, _) = S::new_state(Some(1));
+        let state_for_writer = state.clone();
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        let writer_handle = thread::spawn(move || {
+            let _guard = lock::write(&state_for_writer).unwrap();
+            locked_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        match state.try_get_timeout(Duration::from_millis(20)) {
+            Err(StateError::Timeout(waited)) => assert_eq!(waited, Duration::from_millis(20)),
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+
+        release_tx.send(()).unwrap();
+        writer_handle.join().unwrap();
+        assert_eq!(state.get(), Some(1));
+    }
+}
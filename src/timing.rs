@@ -0,0 +1,286 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::runtime::Handle;
+use tokio::time::Instant;
+
+use crate::{error, ManagedState, StateExt, StateSetter};
+
+/// The timer of a debounced setter.
+struct Debounce<T> {
+    /// When the pending value is written, unless another write comes first.
+    deadline: Instant,
+    /// The value of the most recent write, waiting for the deadline.
+    pending: Option<Option<T>>,
+    /// Whether a task waiting for the deadline is already running.
+    scheduled: bool,
+}
+
+/// Creates a setter which writes to `state` only once the writes have stopped for `delay`.
+///
+/// Every call restarts the delay and replaces the pending value, so a burst of writes results in
+/// a single write of its last value, and in a single notification of the observers. The setter
+/// returns as soon as the value is scheduled; an error of the deferred write cannot be reported
+/// and is dropped.
+///
+/// The timer runs on the tokio runtime in which the setter is created, so the setter itself can
+/// be called from any thread. A setter keeps at most one timer task: calls made while it is
+/// pending only push its deadline back.
+///
+/// # Arguments
+///
+/// * `state` - The state to write to.
+/// * `delay` - The quiet period after which the last value is written.
+///
+/// # Return Value
+///
+/// Returns a `StateSetter<T>` scheduling the writes.
+///
+/// # Panics
+///
+/// Panics if called outside of a tokio runtime.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use state_manager::{debounced_setter, Getter, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let (query, _) = Buffer::new_state(None);
+/// let set_query = debounced_setter(&query, Duration::from_millis(10));
+/// for prefix in ["s", "st", "sta", "stat", "state"] {
+///     set_query(Some(prefix.to_string())).unwrap();
+/// }
+/// tokio::time::sleep(Duration::from_millis(50)).await;
+/// assert_eq!(query.get(), Some(String::from("state")));
+/// # });
+/// ```
+pub fn debounced_setter<T>(state: &ManagedState<T>, delay: Duration) -> StateSetter<T>
+    where T: Send + Sync + 'static
+{
+    let runtime = Handle::current();
+    let state = state.clone();
+    let debounce = Arc::new(Mutex::new(Debounce {
+        deadline: Instant::now(),
+        pending: None,
+        scheduled: false,
+    }));
+    Arc::new(move |data: Option<T>| -> error::Result<()> {
+        let mut timer = debounce.lock()?;
+        timer.deadline = Instant::now() + delay;
+        timer.pending = Some(data);
+        if !timer.scheduled {
+            timer.scheduled = true;
+            let state = state.clone();
+            let debounce = debounce.clone();
+            let mut deadline = timer.deadline;
+            runtime.spawn(async move {
+                loop {
+                    tokio::time::sleep_until(deadline).await;
+                    let Ok(mut timer) = debounce.lock() else {
+                        return;
+                    };
+                    if timer.deadline > deadline {
+                        // Срок отодвинут новыми вызовами => ждём дальше той же задачей
+                        deadline = timer.deadline;
+                        continue;
+                    }
+                    timer.scheduled = false;
+                    let data = timer.pending.take();
+                    drop(timer);
+                    if let Some(data) = data {
+                        let _ = state.set(data);
+                    }
+                    return;
+                }
+            });
+        }
+        Ok(())
+    })
+}
+
+/// The window of a throttled setter.
+struct Throttle<T> {
+    /// When the next write may be applied immediately.
+    opens_at: Option<Instant>,
+    /// The most recent write dropped while the window was closed.
+    pending: Option<Option<T>>,
+    /// Whether a task applying `pending` is already scheduled.
+    scheduled: bool,
+}
+
+/// Creates a setter which writes to `state` at most once per `interval`.
+///
+/// The first write is applied immediately and closes the window for `interval`. Writes made
+/// while the window is closed are not applied; once it opens again, the most recent of them is
+/// written and the window closes anew. Observers are therefore notified at most once per
+/// `interval`, and never miss the final value of a burst. An error of a deferred write cannot be
+/// reported and is dropped.
+///
+/// The timers run on the tokio runtime in which the setter is created, so the setter itself can
+/// be called from any thread.
+///
+/// # Arguments
+///
+/// * `state` - The state to write to.
+/// * `interval` - The minimum time between two writes.
+///
+/// # Return Value
+///
+/// Returns a `StateSetter<T>` applying or scheduling the writes. It returns the error of an
+/// immediate write.
+///
+/// # Panics
+///
+/// Panics if called outside of a tokio runtime.
+pub fn throttled_setter<T>(state: &ManagedState<T>, interval: Duration) -> StateSetter<T>
+    where T: Send + Sync + 'static
+{
+    let runtime = Handle::current();
+    let state = state.clone();
+    let throttle = Arc::new(Mutex::new(Throttle {
+        opens_at: None,
+        pending: None,
+        scheduled: false,
+    }));
+    Arc::new(move |data: Option<T>| -> error::Result<()> {
+        let mut window = throttle.lock()?;
+        let now = Instant::now();
+        match window.opens_at {
+            Some(opens_at) if now < opens_at => {
+                window.pending = Some(data);
+                if !window.scheduled {
+                    window.scheduled = true;
+                    let state = state.clone();
+                    let throttle = throttle.clone();
+                    runtime.spawn(async move {
+                        tokio::time::sleep_until(opens_at).await;
+                        let Ok(mut window) = throttle.lock() else {
+                            return;
+                        };
+                        window.scheduled = false;
+                        if let Some(data) = window.pending.take() {
+                            window.opens_at = Some(Instant::now() + interval);
+                            // Наблюдатели могут снова вызвать сеттер => пишем без блокировки окна
+                            drop(window);
+                            let _ = state.set(data);
+                        }
+                    });
+                }
+                Ok(())
+            },
+            _ => {
+                window.opens_at = Some(now + interval);
+                window.pending = None;
+                drop(window);
+                state.set(data)
+            },
+        }
+    })
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::tests::S;
+    use crate::{debounced_setter, throttled_setter, Getter, Observable, StateManager};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_debounce_coalesces_burst() {
+        let (state, _) = S::new_state(Some(0));
+        let notifications = Arc::new(AtomicUsize::new(0));
+        let notifications_clone = notifications.clone();
+        let _subscription = state.on_change(move |_| {
+            notifications_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let set_state = debounced_setter(&state, Duration::from_millis(100));
+        for value in 1..=5 {
+            set_state(Some(value)).unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        // Задержка ещё не истекла
+        assert_eq!(state.get(), Some(0));
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(state.get(), Some(5));
+        assert_eq!(notifications.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_debounce_keeps_one_timer() {
+        let (state, _) = S::new_state(Some(0));
+        let set_state = debounced_setter(&state, Duration::from_millis(100));
+        let metrics = tokio::runtime::Handle::current().metrics();
+
+        for value in 1..=5 {
+            set_state(Some(value)).unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        // Вся серия обслуживается одной задачей
+        assert_eq!(metrics.num_alive_tasks(), 1);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(state.get(), Some(5));
+        assert_eq!(metrics.num_alive_tasks(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_debounce_separate_bursts() {
+        let (state, _) = S::new_state(Some(0));
+        let set_state = debounced_setter(&state, Duration::from_millis(100));
+
+        set_state(Some(1)).unwrap();
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(state.get(), Some(1));
+
+        set_state(Some(2)).unwrap();
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(state.get(), Some(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttle_limits_writes() {
+        let (state, _) = S::new_state(Some(0));
+        let notifications = Arc::new(AtomicUsize::new(0));
+        let notifications_clone = notifications.clone();
+        let _subscription = state.on_change(move |_| {
+            notifications_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let set_state = throttled_setter(&state, Duration::from_millis(100));
+        // Первая запись применяется сразу
+        set_state(Some(1)).unwrap();
+        assert_eq!(state.get(), Some(1));
+
+        for value in 2..=5 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            set_state(Some(value)).unwrap();
+        }
+        assert_eq!(state.get(), Some(1));
+
+        // Когда окно открывается, применяется последнее отброшенное значение
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(state.get(), Some(5));
+        assert_eq!(notifications.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttle_open_window_applies_immediately() {
+        let (state, _) = S::new_state(Some(0));
+        let set_state = throttled_setter(&state, Duration::from_millis(100));
+
+        set_state(Some(1)).unwrap();
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        set_state(Some(2)).unwrap();
+        assert_eq!(state.get(), Some(2));
+    }
+}
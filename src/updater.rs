@@ -0,0 +1,373 @@
+use core::mem;
+
+use crate::error::{self, StateError};
+use crate::{lock, StateExt};
+
+/// The `Updater` trait provides an `update` method for mutating the state in place.
+///
+/// Unlike the `StateSetter`, which replaces the whole value, `update` hands the current
+/// value to a closure under a single write lock. This removes the read-modify-write race
+/// that appears when the value is read, cloned, changed and set back in separate steps.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Getter, StateBuffer, StateManager, Updater};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (counter, _) = Buffer::new_state(Some(0));
+/// counter.update(|value| {
+///     if let Some(count) = value {
+///         *count += 1;
+///     }
+/// }).unwrap();
+/// assert_eq!(counter.get(), Some(1));
+/// ```
+pub trait Updater<T> {
+    /// Applies the given closure to the current value of the state under a single write lock.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure receiving a mutable reference to the stored `Option<T>`.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` if the closure was applied, or a `StateError` if the lock could not be acquired.
+    fn update<F: FnOnce(&mut Option<T>)>(&self, f: F) -> error::Result<()>;
+
+    /// Applies several modifications to the state as one atomic batch.
+    ///
+    /// All mutations made by `f` happen under a single write lock, and observers are notified
+    /// once, after the lock is released. Neither readers nor subscribers can observe a partially
+    /// applied batch: they see either the value before the batch or the final value.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure performing all modifications of the stored `Option<T>`.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` once the batch is applied, or a `StateError` if the lock could not be acquired.
+    fn batch<F: FnOnce(&mut Option<T>)>(&self, f: F) -> error::Result<()>;
+
+    /// Replaces the value with the result of `f` and returns the value it had before.
+    ///
+    /// Reading, transforming and storing happen under a single write lock. `f` receives a clone of
+    /// the value, so if it panics the state keeps its current value.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure computing the new value from the current one.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the value before the update, or a `StateError` if the lock could not be acquired.
+    fn get_and_update<F: FnOnce(Option<T>) -> Option<T>>(&self, f: F) -> error::Result<Option<T>>
+        where T: Clone;
+
+    /// Replaces the value with the result of `f` and returns the new value.
+    ///
+    /// Reading, transforming and storing happen under a single write lock. `f` receives a clone of
+    /// the value, so if it panics the state keeps its current value.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure computing the new value from the current one.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the value after the update, or a `StateError` if the lock could not be acquired.
+    fn update_and_get<F: FnOnce(Option<T>) -> Option<T>>(&self, f: F) -> error::Result<Option<T>>
+        where T: Clone;
+
+    /// Mutates the stored value in place, e.g. a single field of a large struct, without cloning it.
+    ///
+    /// Unlike `update`, the closure receives the value itself rather than the `Option`, so it can
+    /// only be used while the state holds a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure receiving a mutable reference to the value.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` once `f` is applied, `StateError::Empty` if the state is empty, or a
+    /// `StateError` if the lock could not be acquired. An empty state is left untouched and its
+    /// observers are not notified.
+    fn partial_update<F: FnOnce(&mut T)>(&self, f: F) -> error::Result<()>;
+
+    /// Applies the given closure like `update`, unless the lock is currently held.
+    ///
+    /// Intended for best-effort, low-priority updates which should yield to other accesses
+    /// instead of waiting for them.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure receiving a mutable reference to the stored `Option<T>`.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(true)` if the closure was applied, `Ok(false)` if the lock was busy and the
+    /// closure was not called, or a `StateError` if the lock is poisoned.
+    fn try_update<F: FnOnce(&mut Option<T>)>(&self, f: F) -> error::Result<bool>;
+}
+
+impl<T, S> Updater<T> for S
+where S: StateExt<T>
+{
+    fn update<F: FnOnce(&mut Option<T>)>(&self, f: F) -> error::Result<()> {
+        let mut state_guard = self.write_for_setter()?;
+        f(&mut state_guard);
+        self.publish(state_guard);
+        Ok(())
+    }
+
+    fn batch<F: FnOnce(&mut Option<T>)>(&self, f: F) -> error::Result<()> {
+        self.update(f)
+    }
+
+    fn get_and_update<F: FnOnce(Option<T>) -> Option<T>>(&self, f: F) -> error::Result<Option<T>>
+        where T: Clone
+    {
+        transform(self, f, Returned::Previous)
+    }
+
+    fn update_and_get<F: FnOnce(Option<T>) -> Option<T>>(&self, f: F) -> error::Result<Option<T>>
+        where T: Clone
+    {
+        transform(self, f, Returned::Updated)
+    }
+
+    fn partial_update<F: FnOnce(&mut T)>(&self, f: F) -> error::Result<()> {
+        let mut state_guard = self.write_for_setter()?;
+        let Some(value) = state_guard.as_mut() else {
+            return Err(StateError::Empty);
+        };
+        f(value);
+        self.publish(state_guard);
+        Ok(())
+    }
+
+    fn try_update<F: FnOnce(&mut Option<T>)>(&self, f: F) -> error::Result<bool> {
+        let Some(mut state_guard) = lock::try_write(self.state())? else {
+            return Ok(false);
+        };
+        if self.is_frozen_locked() {
+            return Err(StateError::Frozen);
+        }
+        f(&mut state_guard);
+        self.publish(state_guard);
+        Ok(true)
+    }
+}
+
+/// Which value `transform` returns.
+enum Returned {
+    Previous,
+    Updated,
+}
+
+/// Replaces the value of the state with the result of `f` under a single write lock,
+/// returning either the previous or the updated value.
+fn transform<T, S, F>(state: &S, f: F, returned: Returned) -> error::Result<Option<T>>
+    where
+    T: Clone,
+    S: StateExt<T>,
+    F: FnOnce(Option<T>) -> Option<T>,
+{
+    let mut state_guard = state.write_for_setter()?;
+    // `f` получает копию, так что паника в нём не затрагивает хранимое значение
+    let updated = f(state_guard.clone());
+    let previous = mem::replace(&mut *state_guard, updated);
+    let result = match returned {
+        Returned::Previous => previous,
+        Returned::Updated => state_guard.clone(),
+    };
+    state.publish(state_guard);
+    Ok(result)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::mpsc;
+    use std::thread;
+
+    use crate::tests::S;
+    #[cfg(feature = "std")]
+    use crate::Observable;
+    use crate::error::StateError;
+    use crate::{Getter, StateManager, Updater};
+
+    #[test]
+    fn test_update_in_place() {
+        let (state, _) = S::new_state(Some(String::from("state")));
+        state.update(|value| {
+            if let Some(s) = value {
+                s.push_str("-manager");
+            }
+        }).unwrap();
+        assert_eq!(state.get(), Some(String::from("state-manager")));
+    }
+
+    #[test]
+    fn test_update_empty_state() {
+        let (state, _) = S::new_state(None::<i32>);
+        state.update(|value| *value = Some(1)).unwrap();
+        assert_eq!(state.get(), Some(1));
+    }
+
+    #[test]
+    fn test_concurrent_updates_do_not_lose_increments() {
+        let (state, _) = S::new_state(Some(0));
+        let mut handles = vec![];
+
+        for _ in 0..2 {
+            let state_clone = state.clone();
+            let handle = thread::spawn(move || {
+                for _ in 0..1000 {
+                    state_clone.update(|value| {
+                        if let Some(count) = value {
+                            *count += 1;
+                        }
+                    }).unwrap();
+                }
+            });
+            handles.push(handle);
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        // Каждое инкрементирование выполняется под одной блокировкой => ни одно не потеряно
+        assert_eq!(state.get(), Some(2000));
+    }
+
+    #[test]
+    fn test_get_and_update_returns_previous() {
+        let (state, _) = S::new_state(Some(1));
+        let previous = state.get_and_update(|value| value.map(|v| v + 1)).unwrap();
+        assert_eq!(previous, Some(1));
+        assert_eq!(state.get(), Some(2));
+    }
+
+    #[test]
+    fn test_get_and_update_empty_state() {
+        let (state, _) = S::new_state(None);
+        let previous = state.get_and_update(|value| Some(value.unwrap_or(0) + 10)).unwrap();
+        assert_eq!(previous, None);
+        assert_eq!(state.get(), Some(10));
+    }
+
+    #[test]
+    fn test_update_and_get_appends_to_string() {
+        let (state, _) = S::new_state(Some(String::from("state")));
+        let updated = state.update_and_get(|value| value.map(|s| s + "-manager")).unwrap();
+        assert_eq!(updated, Some(String::from("state-manager")));
+        assert_eq!(state.get(), updated);
+    }
+
+    #[test]
+    fn test_update_and_get_to_none() {
+        let (state, _) = S::new_state(Some(1));
+        assert_eq!(state.update_and_get(|_| None).unwrap(), None);
+        assert_eq!(state.get(), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[derive(Clone, Debug, Default, PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_batch_subscriber_sees_final_value() {
+        let (state, _) = S::new_state(Some(Point { x: 0, y: 0 }));
+        let receiver = state.subscribe();
+
+        state.batch(|value| {
+            if let Some(point) = value {
+                point.x = 1;
+                point.y = 2;
+            }
+        }).unwrap();
+
+        // Подписчик получает только итоговое значение, без промежуточных состояний
+        assert_eq!(receiver.try_recv().unwrap(), Some(Point { x: 1, y: 2 }));
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(state.get(), Some(Point { x: 1, y: 2 }));
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Settings {
+        title: String,
+        volume: u8,
+    }
+
+    #[test]
+    fn test_partial_update_mutates_field() {
+        let (state, _) = S::new_state(Some(Settings { title: String::from("main"), volume: 3 }));
+        state.partial_update(|settings| settings.volume = 7).unwrap();
+        assert_eq!(state.get(), Some(Settings { title: String::from("main"), volume: 7 }));
+    }
+
+    #[test]
+    fn test_partial_update_empty_state() {
+        let (state, _) = S::new_state(None::<Settings>);
+        let result = state.partial_update(|settings| settings.volume = 7);
+        assert_eq!(result, Err(StateError::Empty));
+        // Пустое состояние остаётся пустым
+        assert_eq!(state.get(), None);
+    }
+
+    #[test]
+    fn test_try_update_skips_busy_lock() {
+        let (state, _) = S::new_state(Some(1));
+        let state_for_holder = state.clone();
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        let holder_handle = thread::spawn(move || {
+            state_for_holder.update(|_| {
+                locked_tx.send(()).unwrap();
+                release_rx.recv().unwrap();
+            }).unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        // Блокировка занята => замыкание не вызывается
+        assert_eq!(state.try_update(|value| *value = Some(2)), Ok(false));
+
+        release_tx.send(()).unwrap();
+        holder_handle.join().unwrap();
+        assert_eq!(state.try_update(|value| *value = Some(3)), Ok(true));
+        assert_eq!(state.get(), Some(3));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_transform_panic_keeps_value() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        use crate::lock;
+
+        // На бэкенде std паника отравляет блокировку, поэтому у каждого вызова своё состояние
+        let (previous, _) = S::new_state(Some(1));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            previous.get_and_update(|_| panic!("transform failed"))
+        }));
+        assert!(result.is_err());
+        // Паника в замыкании не опустошает состояние
+        assert_eq!(*lock::read_recovering(&previous), Some(1));
+
+        let (updated, _) = S::new_state(Some(1));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            updated.update_and_get(|_| panic!("transform failed"))
+        }));
+        assert!(result.is_err());
+        assert_eq!(*lock::read_recovering(&updated), Some(1));
+    }
+}
@@ -0,0 +1,235 @@
+use crate::{error, lock, ManagedState, StateExt};
+
+/// The `Versioned` trait provides cheap change detection through version numbers.
+///
+/// Every `ManagedState` carries a counter which is incremented by every write, whether it is made
+/// by the setter or by the other traits of this crate, even if the written value equals the
+/// previous one. Reads do not change it. Comparing versions tells whether a state changed since it was
+/// last looked at, without comparing or even cloning its value.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{StateBuffer, StateManager, Versioned};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, set_state) = Buffer::new_state(Some(1));
+/// let seen = state.version();
+///
+/// set_state(Some(2)).unwrap();
+/// assert_ne!(state.version(), seen);
+/// ```
+pub trait Versioned<T> {
+    /// Returns the current version of the state.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the number of writes made to the state since its creation.
+    fn version(&self) -> u64;
+
+    /// Returns the current value together with its version.
+    ///
+    /// Both are read under the same read lock, so the version always belongs to the returned
+    /// value, even while other threads write to the state. A view can record the version it was
+    /// rendered from and later check whether it is stale.
+    ///
+    /// # Return Value
+    ///
+    /// Returns the value, or `None` if the state is empty or the lock is poisoned, and the
+    /// version of the state.
+    fn get_with_version(&self) -> (Option<T>, u64)
+        where T: Clone;
+
+    /// Writes `data` only if the state is still at the `expected` version.
+    ///
+    /// This is an optimistic compare-and-set which, unlike comparing values, does not require
+    /// `T: PartialEq`: read the value with `get_with_version`, compute the new value, and write it
+    /// back only if nobody has written in the meantime. The write behaves like the setter,
+    /// including recording the history and failing on a frozen state.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The new value of the state.
+    /// * `expected` - The version the state must be at.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(true)` if the value was written, `Ok(false)` if the version did not match,
+    /// or a `StateError` if the write failed.
+    fn set_if_version(&self, data: Option<T>, expected: u64) -> error::Result<bool>;
+
+    /// Creates a `ChangeTracker` for polling the state for changes.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a tracker which reports the writes made after this call.
+    fn track(&self) -> ChangeTracker<T>;
+}
+
+/// A poller detecting the changes of a state by its version.
+///
+/// Created by `Versioned::track`. It suits loops which cannot use callbacks, e.g. a game or UI
+/// loop checking its inputs once per frame: polling is cheap and clones the value only when it
+/// changed. Several writes between two polls are reported once, with the latest value.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{StateBuffer, StateManager, Versioned};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (score, set_score) = Buffer::new_state(Some(0));
+/// let mut tracker = score.track();
+/// assert_eq!(tracker.poll(), None);
+///
+/// set_score(Some(10)).unwrap();
+/// assert_eq!(tracker.poll(), Some(Some(10)));
+/// assert_eq!(tracker.poll(), None);
+/// ```
+pub struct ChangeTracker<T> {
+    state: ManagedState<T>,
+    seen: u64,
+}
+
+impl<T> ChangeTracker<T> {
+    /// Checks whether the state changed since the previous poll, or since the tracker was
+    /// created.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Some` with the current value if the state was written in the meantime, or `None`
+    /// if it was not.
+    pub fn poll(&mut self) -> Option<Option<T>>
+        where T: Clone
+    {
+        if self.state.current_version() == self.seen {
+            return None;
+        }
+        let (value, version) = self.state.get_with_version();
+        self.seen = version;
+        Some(value)
+    }
+}
+
+impl<T> Versioned<T> for ManagedState<T> {
+    fn version(&self) -> u64 {
+        self.current_version()
+    }
+
+    fn get_with_version(&self) -> (Option<T>, u64)
+        where T: Clone
+    {
+        match lock::read(self) {
+            Ok(state_guard) => (state_guard.clone(), self.current_version()),
+            Err(_) => (None, self.current_version()),
+        }
+    }
+
+    fn set_if_version(&self, data: Option<T>, expected: u64) -> error::Result<bool> {
+        self.store_if(data, |state| state.current_version() == expected)
+            .map_err(|(_, e)| e)
+    }
+
+    fn track(&self) -> ChangeTracker<T> {
+        ChangeTracker {
+            state: self.clone(),
+            seen: self.current_version(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::thread;
+
+    use crate::tests::S;
+    use crate::{Getter, StateManager, Updater, Versioned};
+
+    #[test]
+    fn test_version_increments_per_set() {
+        let (state, set_state) = S::new_state(Some(0));
+        assert_eq!(state.version(), 0);
+
+        for i in 1..=3 {
+            set_state(Some(i)).unwrap();
+            assert_eq!(state.version(), i as u64);
+        }
+        // Запись тем же значением тоже меняет версию
+        set_state(Some(3)).unwrap();
+        assert_eq!(state.version(), 4);
+
+        state.update(|value| *value = None).unwrap();
+        assert_eq!(state.version(), 5);
+    }
+
+    #[test]
+    fn test_version_unchanged_by_reads() {
+        let (state, set_state) = S::new_state(Some(1));
+        set_state(Some(2)).unwrap();
+        let version = state.version();
+
+        state.get();
+        state.try_get();
+        state.clone().get_or(0);
+        assert_eq!(state.version(), version);
+    }
+
+    #[test]
+    fn test_get_with_version_pairs_match() {
+        let (state, set_state) = S::new_state(Some(0u64));
+        assert_eq!(state.get_with_version(), (Some(0), 0));
+        set_state(Some(1)).unwrap();
+        assert_eq!(state.get_with_version(), (Some(1), 1));
+
+        // Значение i записывается i-й записью => пара всегда совпадает
+        let writer = thread::spawn(move || {
+            for i in 2..=1000 {
+                set_state(Some(i)).unwrap();
+            }
+        });
+        for _ in 0..1000 {
+            let (value, version) = state.get_with_version();
+            assert_eq!(value, Some(version));
+        }
+        writer.join().unwrap();
+        assert_eq!(state.get_with_version(), (Some(1000), 1000));
+    }
+
+    #[test]
+    fn test_set_if_version() {
+        let (state, set_state) = S::new_state(Some(String::from("a")));
+        let (_, seen) = state.get_with_version();
+
+        set_state(Some(String::from("b"))).unwrap();
+        // Версия устарела => запись отклонена
+        assert_eq!(state.set_if_version(Some(String::from("stale")), seen), Ok(false));
+        assert_eq!(state.get(), Some(String::from("b")));
+
+        let (_, current) = state.get_with_version();
+        assert_eq!(state.set_if_version(Some(String::from("c")), current), Ok(true));
+        assert_eq!(state.get_with_version(), (Some(String::from("c")), current + 1));
+    }
+
+    #[test]
+    fn test_change_tracker_poll() {
+        let (state, set_state) = S::new_state(Some(1));
+        set_state(Some(2)).unwrap();
+        let mut tracker = state.track();
+        // Записи до создания трекера не сообщаются
+        assert_eq!(tracker.poll(), None);
+
+        set_state(Some(3)).unwrap();
+        assert_eq!(tracker.poll(), Some(Some(3)));
+        assert_eq!(tracker.poll(), None);
+
+        // Несколько записей между опросами => одно изменение с последним значением
+        set_state(Some(4)).unwrap();
+        set_state(None).unwrap();
+        assert_eq!(tracker.poll(), Some(None));
+        assert_eq!(tracker.poll(), None);
+    }
+}
@@ -0,0 +1,203 @@
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::{error, lock, ManagedState};
+
+/// Wakes the threads waiting for a change of the state.
+///
+/// A waiter checks its predicate while holding `mutex` and releases it only by waiting on
+/// `changed`. A writer acquires `mutex` before notifying, so it cannot wake the waiters between
+/// the check and the wait: every write made after the check is seen by the waiter.
+pub(crate) struct Waiters {
+    mutex: Mutex<()>,
+    changed: Condvar,
+}
+
+impl Waiters {
+    pub(crate) fn new() -> Self {
+        Waiters {
+            mutex: Mutex::new(()),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// Wakes all the waiting threads. Must be called after the new value is written.
+    pub(crate) fn wake(&self) {
+        drop(self.lock());
+        self.changed.notify_all();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ()> {
+        // Мьютекс не защищает данных, поэтому отравление можно игнорировать
+        self.mutex.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The `Wait` trait provides blocking until the state satisfies a condition.
+///
+/// Waiting threads sleep until the state is written, rather than polling it. Every write made
+/// through the setter or the traits of this crate wakes them to re-check their condition.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use state_manager::{StateBuffer, StateManager, Wait};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, set_state) = Buffer::new_state(Some(0));
+/// let handle = thread::spawn(move || set_state(Some(5)).unwrap());
+///
+/// state.wait_until(|value| *value == Some(5)).unwrap();
+/// handle.join().unwrap();
+/// ```
+pub trait Wait<T> {
+    /// Blocks the current thread until `pred` returns `true` for the value of the state.
+    ///
+    /// The predicate is checked immediately, so if it is already satisfied, the method returns
+    /// without waiting. Otherwise it is re-checked after every write. The predicate runs while
+    /// the read lock is held, so it must not write the state.
+    ///
+    /// # Arguments
+    ///
+    /// * `pred` - The condition the value of the state has to satisfy.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(())` once the predicate is satisfied, or a `StateError` if the lock could not
+    /// be acquired.
+    fn wait_until<F: Fn(&Option<T>) -> bool>(&self, pred: F) -> error::Result<()>;
+
+    /// Blocks the current thread until `pred` returns `true` for the value of the state, waiting
+    /// at most `dur`.
+    ///
+    /// Like `wait_until`, the predicate is checked immediately and re-checked after every write,
+    /// so spurious wakeups never end the wait early.
+    ///
+    /// # Arguments
+    ///
+    /// * `pred` - The condition the value of the state has to satisfy.
+    /// * `dur` - The maximum time to wait.
+    ///
+    /// # Return Value
+    ///
+    /// Returns `Ok(true)` if the predicate was satisfied in time, `Ok(false)` on timeout, or a
+    /// `StateError` if the lock could not be acquired.
+    fn wait_until_timeout<F: Fn(&Option<T>) -> bool>(&self, pred: F, dur: Duration) -> error::Result<bool>;
+}
+
+impl<T> Wait<T> for ManagedState<T> {
+    fn wait_until<F: Fn(&Option<T>) -> bool>(&self, pred: F) -> error::Result<()> {
+        let waiters = self.meta().waiters();
+        let mut wait_guard = waiters.lock();
+        loop {
+            if pred(&*lock::read(self)?) {
+                return Ok(());
+            }
+            wait_guard = waiters.changed
+                .wait(wait_guard)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    fn wait_until_timeout<F: Fn(&Option<T>) -> bool>(&self, pred: F, dur: Duration) -> error::Result<bool> {
+        let deadline = Instant::now() + dur;
+        let waiters = self.meta().waiters();
+        let mut wait_guard = waiters.lock();
+        loop {
+            if pred(&*lock::read(self)?) {
+                return Ok(true);
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(false);
+            }
+            wait_guard = waiters.changed
+                .wait_timeout(wait_guard, remaining)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use crate::tests::S;
+    use crate::{Getter, StateManager, Updater, Wait};
+
+    #[test]
+    fn test_wait_until_already_satisfied() {
+        let (state, _) = S::new_state(Some(5));
+        state.wait_until(|value| *value == Some(5)).unwrap();
+    }
+
+    #[test]
+    fn test_wait_until_set_from_another_thread() {
+        let (state, set_state) = S::new_state(Some(0));
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            set_state(Some(5)).unwrap();
+        });
+
+        state.wait_until(|value| *value == Some(5)).unwrap();
+        assert_eq!(state.get(), Some(5));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_wait_until_skips_unsatisfying_values() {
+        let (state, _) = S::new_state(Some(0));
+        let state_for_writer = state.clone();
+        let handle = thread::spawn(move || {
+            // Каждое изменение будит ожидающий поток, но условие выполняется только в конце
+            for _ in 0..5 {
+                thread::sleep(Duration::from_millis(5));
+                state_for_writer.update(|value| *value = value.map(|v| v + 1)).unwrap();
+            }
+        });
+
+        state.wait_until(|value| *value == Some(5)).unwrap();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_wait_until_timeout_expires() {
+        let (state, set_state) = S::new_state(Some(0));
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(5));
+            set_state(Some(1)).unwrap();
+        });
+
+        let started = Instant::now();
+        let satisfied = state.wait_until_timeout(|value| *value == Some(5), Duration::from_millis(50)).unwrap();
+        assert!(!satisfied);
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_wait_until_timeout_satisfied_in_time() {
+        let (state, set_state) = S::new_state(Some(0));
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            set_state(Some(5)).unwrap();
+        });
+
+        let satisfied = state.wait_until_timeout(|value| *value == Some(5), Duration::from_secs(5)).unwrap();
+        assert!(satisfied);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_wait_until_timeout_zero_duration() {
+        // Нулевой таймаут всё равно проверяет условие один раз
+        let (state, _) = S::new_state(Some(5));
+        assert!(state.wait_until_timeout(|value| *value == Some(5), Duration::ZERO).unwrap());
+        assert!(!state.wait_until_timeout(|value| value.is_none(), Duration::ZERO).unwrap());
+    }
+}
@@ -0,0 +1,183 @@
+use alloc::sync::{Arc, Weak};
+
+use crate::lock::RwLock;
+use crate::{error, Getter, State};
+
+/// A weak reference to a state, which does not keep its value alive.
+///
+/// Holding a `WeakState` instead of a `State` breaks reference cycles, e.g. between a callback
+/// registered on a state and the state it writes to. Use `upgrade` to access the state while it
+/// is still alive, or read it directly through `Getter`, which upgrades transparently and treats
+/// a dropped state as empty.
+///
+/// Like `State`, it is a plain type alias: `Arc::downgrade` and `Weak::upgrade` work on it as
+/// well as `downgrade` and `upgrade`.
+pub type WeakState<T> = Weak<RwLock<Option<T>>>;
+
+/// Creates a weak reference to `state`.
+///
+/// # Arguments
+///
+/// * `state` - The state to reference.
+///
+/// # Return Value
+///
+/// Returns a `WeakState<T>` which does not prevent the state from being dropped.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{downgrade, upgrade, StateBuffer, StateManager};
+///
+/// struct Buffer;
+/// impl StateBuffer for Buffer {}
+///
+/// let (state, set_state) = Buffer::new_state(Some(1));
+/// let weak = downgrade(&state);
+/// assert!(upgrade(&weak).is_some());
+///
+/// drop(state);
+/// drop(set_state);
+/// assert!(upgrade(&weak).is_none());
+/// ```
+pub fn downgrade<T>(state: &State<T>) -> WeakState<T> {
+    Arc::downgrade(state)
+}
+
+/// Restores a state from a weak reference.
+///
+/// # Arguments
+///
+/// * `weak` - The weak reference created by `downgrade`.
+///
+/// # Return Value
+///
+/// Returns the `State<T>` sharing the value with the downgraded state, or `None` if every
+/// clone of the state and its setter were dropped. The returned state carries no metadata, so
+/// writes made through it are not published to the observers of the downgraded state.
+pub fn upgrade<T>(weak: &WeakState<T>) -> Option<State<T>> {
+    weak.upgrade()
+}
+
+impl<T> Getter<T> for WeakState<T>
+where T: Clone
+{
+    fn get(&self) -> Option<T> {
+        upgrade(self)?.get()
+    }
+
+    fn try_get(&self) -> Option<T> {
+        upgrade(self)?.try_get()
+    }
+
+    /// Returns `Ok(None)` if the state was dropped.
+    fn try_get_result(&self) -> error::Result<Option<T>> {
+        match upgrade(self) {
+            Some(state) => state.try_get_result(),
+            None => Ok(None),
+        }
+    }
+
+    fn get_or(&self, default: T) -> T {
+        self.get().unwrap_or(default)
+    }
+
+    fn get_or_default(&self) -> T
+        where T: Default
+    {
+        self.get().unwrap_or_default()
+    }
+
+    fn get_or_else<F: FnOnce() -> T>(&self, f: F) -> T {
+        self.get().unwrap_or_else(f)
+    }
+
+    fn filter_get<F: Fn(&T) -> bool>(&self, pred: F) -> Option<T> {
+        upgrade(self)?.filter_get(pred)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::tests::S;
+    #[cfg(feature = "std")]
+    use crate::Observable;
+    use crate::{downgrade, upgrade, Getter, StateManager};
+
+    #[test]
+    fn test_upgrade_while_alive() {
+        let (state, set_state) = S::new_state(Some(1));
+        let weak = downgrade(&state);
+
+        set_state(Some(2)).unwrap();
+        assert_eq!(upgrade(&weak).unwrap().get(), Some(2));
+    }
+
+    #[test]
+    fn test_weak_state_is_a_weak_reference() {
+        use std::sync::Arc;
+        use crate::WeakState;
+
+        let (state, _set_state) = S::new_state(Some(1));
+        let weak: WeakState<i32> = Arc::downgrade(&state);
+        assert_eq!(weak.get(), Some(1));
+        assert!(Arc::ptr_eq(&upgrade(&weak).unwrap(), &state));
+        assert!(Arc::ptr_eq(&downgrade(&state).upgrade().unwrap(), &state));
+    }
+
+    #[test]
+    fn test_upgrade_after_drop() {
+        let (state, set_state) = S::new_state(Some(1));
+        let weak = downgrade(&state);
+        let weak_clone = weak.clone();
+
+        // Сеттер тоже удерживает состояние
+        drop(state);
+        assert!(upgrade(&weak).is_some());
+        drop(set_state);
+        assert!(upgrade(&weak).is_none());
+        assert!(upgrade(&weak_clone).is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_weak_breaks_cycle() {
+        use crate::StateExt;
+
+        let (source, set_source) = S::new_state(Some(1));
+        let (target, _) = S::new_state(Some(0));
+        let weak_target = downgrade(&target);
+        let _subscription = source.on_change(move |value| {
+            if let Some(target) = upgrade(&weak_target) {
+                target.set(*value).unwrap();
+            }
+        });
+
+        set_source(Some(2)).unwrap();
+        assert_eq!(target.get(), Some(2));
+
+        // Колбэк не удерживает целевое состояние
+        let weak = downgrade(&target);
+        drop(target);
+        assert!(upgrade(&weak).is_none());
+        set_source(Some(3)).unwrap();
+    }
+
+    #[test]
+    fn test_weak_getter() {
+        let (state, set_state) = S::new_state(Some(1));
+        let weak = downgrade(&state);
+        assert_eq!(weak.get(), Some(1));
+
+        set_state(Some(2)).unwrap();
+        assert_eq!(weak.get_or(0), 2);
+        assert_eq!(weak.filter_get(|value| *value > 1), Some(2));
+
+        // Исходное состояние удалено => читается как пустое
+        drop(state);
+        drop(set_state);
+        assert_eq!(weak.get(), None);
+        assert_eq!(weak.try_get_result(), Ok(None));
+        assert_eq!(weak.get_or(0), 0);
+    }
+}
@@ -0,0 +1,28 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Derives the `StateBuffer` marker trait, making `StateManager` available on the type.
+///
+/// # Examples
+///
+/// ```
+/// use state_manager::{Getter, StateBuffer, StateManager};
+///
+/// #[derive(StateBuffer)]
+/// struct Buffer;
+///
+/// let (state, _) = Buffer::new_state(Some(1));
+/// assert_eq!(state.get(), Some(1));
+/// ```
+#[proc_macro_derive(StateBuffer)]
+pub fn derive_state_buffer(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics ::state_manager::StateBuffer for #name #ty_generics #where_clause {}
+    };
+    expanded.into()
+}
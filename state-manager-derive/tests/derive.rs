@@ -0,0 +1,5 @@
+#[test]
+fn test_derive_state_buffer() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/new_state.rs");
+}
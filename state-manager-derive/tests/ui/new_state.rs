@@ -0,0 +1,10 @@
+use state_manager::{Getter, StateBuffer, StateManager};
+
+#[derive(StateBuffer)]
+struct Buffer;
+
+fn main() {
+    let (state, set_state) = Buffer::new_state(Some(String::from("hello")));
+    set_state(Some(String::from("world"))).unwrap();
+    assert_eq!(state.get(), Some(String::from("world")));
+}